@@ -1,11 +1,12 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use ant_mania::simulation::Simulation;
 use ant_mania::{parser, SimulationConfig};
+use ant_mania::bitset::BitSet;
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 
-/// Micro-benchmarks for specific performance-critical operations
+// Micro-benchmarks for specific performance-critical operations
 
 fn create_dense_map(size: usize) -> PathBuf {
     let temp_dir = std::env::temp_dir();
@@ -57,6 +58,21 @@ fn benchmark_rng_performance(c: &mut Criterion) {
         });
     });
     
+    group.bench_function("rng_fill_u32_1M", |b| {
+        let mut buf = vec![0u32; 1000];
+        b.iter(|| {
+            let mut rng = ant_mania::rng::FastRng::new(42);
+            let mut sum = 0u64;
+            
+            // Same 1M values as above, generated in batches of 1000
+            for _ in 0..1_000_000 / buf.len() {
+                rng.fill_u32(&mut buf);
+                sum = buf.iter().fold(sum, |sum, &value| sum.wrapping_add(value as u64));
+            }
+            black_box(sum);
+        });
+    });
+    
     group.finish();
 }
 
@@ -70,19 +86,19 @@ fn benchmark_collision_detection(c: &mut Criterion) {
     group.bench_function("dense_collisions_200_ants", |b| {
         b.iter_batched(
             || {
-                let config = SimulationConfig {
-                    num_ants: 200,
-                    map_file: map_path.to_string(),
-                    max_moves: 50, // Short simulation, focus on collision detection
-                    seed: Some(42),
-                };
+                let config = SimulationConfig::builder()
+                    .ants(200)
+                    .map_file(map_path)
+                    .max_moves(50) // Short simulation, focus on collision detection
+                    .seed(Some(42))
+                    .build();
                 let mut sim = Simulation::new(config);
                 parser::parse_map_file(&mut sim, map_path).unwrap();
-                sim.initialize_ants(200);
+                sim.initialize_ants(200).unwrap();
                 sim
             },
             |mut sim| {
-                black_box(sim.run_simulation());
+                black_box(sim.run_simulation())
             },
             criterion::BatchSize::SmallInput,
         );
@@ -101,19 +117,19 @@ fn benchmark_direction_selection(c: &mut Criterion) {
     group.bench_function("direction_lookup_heavy", |b| {
         b.iter_batched(
             || {
-                let config = SimulationConfig {
-                    num_ants: 100,
-                    map_file: map_path.to_string(),
-                    max_moves: 500, // Many moves to test direction selection
-                    seed: Some(42),
-                };
+                let config = SimulationConfig::builder()
+                    .ants(100)
+                    .map_file(map_path)
+                    .max_moves(500) // Many moves to test direction selection
+                    .seed(Some(42))
+                    .build();
                 let mut sim = Simulation::new(config);
                 parser::parse_map_file(&mut sim, map_path).unwrap();
-                sim.initialize_ants(100);
+                sim.initialize_ants(100).unwrap();
                 sim
             },
             |mut sim| {
-                black_box(sim.run_simulation());
+                black_box(sim.run_simulation())
             },
             criterion::BatchSize::SmallInput,
         );
@@ -137,19 +153,19 @@ fn benchmark_memory_access_patterns(c: &mut Criterion) {
             |b, &num_ants| {
                 b.iter_batched(
                     || {
-                        let config = SimulationConfig {
-                            num_ants,
-                            map_file: map_path.to_string(),
-                            max_moves: 100,
-                            seed: Some(42),
-                        };
+                        let config = SimulationConfig::builder()
+                            .ants(num_ants)
+                            .map_file(map_path)
+                            .max_moves(100)
+                            .seed(Some(42))
+                            .build();
                         let mut sim = Simulation::new(config);
                         parser::parse_map_file(&mut sim, map_path).unwrap();
-                        sim.initialize_ants(num_ants);
+                        sim.initialize_ants(num_ants).unwrap();
                         sim
                     },
                     |mut sim| {
-                        black_box(sim.run_simulation());
+                        black_box(sim.run_simulation())
                     },
                     criterion::BatchSize::SmallInput,
                 );
@@ -161,11 +177,39 @@ fn benchmark_memory_access_patterns(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_flag_storage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flag_storage");
+    
+    // Worst case for the termination scan: only the last entry is still set
+    let size = 200_000;
+    let mut bytes = vec![false; size];
+    bytes[size - 1] = true;
+    let mut bits = BitSet::with_len(size, false);
+    bits.set(size - 1, true);
+    
+    println!(
+        "flag_storage memory for {size} entries: Vec<bool> {} bytes, BitSet {} bytes",
+        bytes.len() * std::mem::size_of::<bool>(),
+        bits.memory_bytes()
+    );
+    
+    group.bench_function("vec_bool_any_200k", |b| {
+        b.iter(|| black_box(black_box(&bytes).iter().any(|&alive| alive)));
+    });
+    
+    group.bench_function("bitset_any_200k", |b| {
+        b.iter(|| black_box(black_box(&bits).any()));
+    });
+    
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_rng_performance,
     benchmark_collision_detection,
     benchmark_direction_selection,
-    benchmark_memory_access_patterns
+    benchmark_memory_access_patterns,
+    benchmark_flag_storage
 );
 criterion_main!(benches);
\ No newline at end of file
@@ -14,16 +14,16 @@ fn benchmark_real_maps(c: &mut Criterion) {
                 num_ants,
                 |b, &num_ants| {
                     b.iter(|| {
-                        let config = SimulationConfig {
-                            num_ants,
-                            map_file: "maps/hiveum_map_small.txt".to_string(),
-                            max_moves: 10000,
-                            seed: Some(42),
-                        };
+                        let config = SimulationConfig::builder()
+                            .ants(num_ants)
+                            .map_file("maps/hiveum_map_small.txt")
+                            .max_moves(10000)
+                            .seed(Some(42))
+                            .build();
                         let mut sim = Simulation::new(config);
                         parser::parse_map_file(&mut sim, "maps/hiveum_map_small.txt").unwrap();
-                        sim.initialize_ants(num_ants);
-                        black_box(sim.run_simulation());
+                        sim.initialize_ants(num_ants).unwrap();
+                        black_box(sim.run_simulation())
                     });
                 },
             );
@@ -38,16 +38,16 @@ fn benchmark_real_maps(c: &mut Criterion) {
                 num_ants,
                 |b, &num_ants| {
                     b.iter(|| {
-                        let config = SimulationConfig {
-                            num_ants,
-                            map_file: "maps/hiveum_map_medium.txt".to_string(),
-                            max_moves: 10000,
-                            seed: Some(42),
-                        };
+                        let config = SimulationConfig::builder()
+                            .ants(num_ants)
+                            .map_file("maps/hiveum_map_medium.txt")
+                            .max_moves(10000)
+                            .seed(Some(42))
+                            .build();
                         let mut sim = Simulation::new(config);
                         parser::parse_map_file(&mut sim, "maps/hiveum_map_medium.txt").unwrap();
-                        sim.initialize_ants(num_ants);
-                        black_box(sim.run_simulation());
+                        sim.initialize_ants(num_ants).unwrap();
+                        black_box(sim.run_simulation())
                     });
                 },
             );
@@ -64,22 +64,22 @@ fn benchmark_single_iteration(c: &mut Criterion) {
     if Path::new("maps/hiveum_map_medium.txt").exists() {
         group.bench_function("medium_map_1000_ants_single_iteration", |b| {
             // Setup once outside the timing loop
-            let config = SimulationConfig {
-                num_ants: 1000,
-                map_file: "maps/hiveum_map_medium.txt".to_string(),
-                max_moves: 1, // Only one move to measure single iteration
-                seed: Some(42),
-            };
+            let config = SimulationConfig::builder()
+                .ants(1000)
+                .map_file("maps/hiveum_map_medium.txt")
+                .max_moves(1) // Only one move to measure single iteration
+                .seed(Some(42))
+                .build();
             
             b.iter_batched(
                 || {
                     let mut sim = Simulation::new(config.clone());
                     parser::parse_map_file(&mut sim, "maps/hiveum_map_medium.txt").unwrap();
-                    sim.initialize_ants(1000);
+                    sim.initialize_ants(1000).unwrap();
                     sim
                 },
                 |mut sim| {
-                    black_box(sim.run_simulation());
+                    black_box(sim.run_simulation())
                 },
                 criterion::BatchSize::LargeInput,
             );
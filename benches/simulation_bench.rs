@@ -72,24 +72,24 @@ fn benchmark_simulation_hot_path(c: &mut Criterion) {
             num_ants,
             |b, &num_ants| {
                 // Setup simulation ONCE outside the timing loop
-                let config = SimulationConfig {
-                    num_ants,
-                    map_file: map_path.to_string(),
-                    max_moves: 10000,
-                    seed: Some(42),
-                };
+                let config = SimulationConfig::builder()
+                    .ants(num_ants)
+                    .map_file(map_path)
+                    .max_moves(10000)
+                    .seed(Some(42))
+                    .build();
                 
                 b.iter_batched(
                     || {
                         // Setup: parse and initialize (not timed)
                         let mut sim = Simulation::new(config.clone());
                         parser::parse_map_file(&mut sim, map_path).unwrap();
-                        sim.initialize_ants(num_ants);
+                        sim.initialize_ants(num_ants).unwrap();
                         sim
                     },
                     |mut sim| {
                         // Only measure the actual simulation
-                        black_box(sim.run_simulation());
+                        black_box(sim.run_simulation())
                     },
                     BatchSize::SmallInput,
                 );
@@ -125,16 +125,16 @@ fn benchmark_parsing_isolated(c: &mut Criterion) {
                         // Create temp file with content
                         let temp_path = std::env::temp_dir().join(format!("parse_bench_{}.txt", std::process::id()));
                         fs::write(&temp_path, &file_content).unwrap();
-                        (temp_path, SimulationConfig {
-                            num_ants: 10,
-                            map_file: path_str.to_string(),
-                            max_moves: 10000,
-                            seed: Some(42),
-                        })
+                        (temp_path, SimulationConfig::builder()
+                            .ants(10)
+                            .map_file(path_str)
+                            .max_moves(10000)
+                            .seed(Some(42))
+                            .build())
                     },
                     |(temp_path, config)| {
                         let mut sim = Simulation::new(config);
-                        black_box(parser::parse_map_file(&mut sim, temp_path.to_str().unwrap()).unwrap());
+                        black_box(parser::parse_map_file(&mut sim, temp_path.to_str().unwrap())).unwrap();
                         cleanup_temp_file(&temp_path);
                     },
                     BatchSize::SmallInput,
@@ -151,6 +151,61 @@ fn benchmark_parsing_isolated(c: &mut Criterion) {
     group.finish();
 }
 
+/// BufReader vs memory-mapped parsing on large grid maps (per-line allocations dominate the former)
+#[cfg(feature = "mmap")]
+fn benchmark_parsing_mmap(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parsing_mmap");
+    group.sample_size(20);
+    
+    for side in [30, 100, 300] {
+        let file_path = std::env::temp_dir().join(format!("grid_map_{}_pid_{}.txt", side, std::process::id()));
+        let mut file = std::io::BufWriter::new(fs::File::create(&file_path).unwrap());
+        for row in 0..side {
+            for col in 0..side {
+                let mut line = format!("C{}_{}", row, col);
+                if row > 0 {
+                    line.push_str(&format!(" north=C{}_{}", row - 1, col));
+                }
+                if row + 1 < side {
+                    line.push_str(&format!(" south=C{}_{}", row + 1, col));
+                }
+                if col + 1 < side {
+                    line.push_str(&format!(" east=C{}_{}", row, col + 1));
+                }
+                if col > 0 {
+                    line.push_str(&format!(" west=C{}_{}", row, col - 1));
+                }
+                writeln!(file, "{}", line).unwrap();
+            }
+        }
+        drop(file);
+        let path_str = file_path.to_str().unwrap();
+        
+        group.bench_with_input(BenchmarkId::new("bufreader", side * side), path_str, |b, path| {
+            b.iter(|| {
+                let mut sim = Simulation::new(SimulationConfig::default());
+                parser::parse_map_file(&mut sim, path).unwrap();
+                black_box(sim)
+            });
+        });
+        
+        group.bench_with_input(BenchmarkId::new("mmap", side * side), path_str, |b, path| {
+            b.iter(|| {
+                let mut sim = Simulation::new(SimulationConfig::default());
+                parser::parse_map_mmap(&mut sim, path).unwrap();
+                black_box(sim)
+            });
+        });
+        
+        cleanup_temp_file(&file_path);
+    }
+    
+    group.finish();
+}
+
+#[cfg(not(feature = "mmap"))]
+fn benchmark_parsing_mmap(_c: &mut Criterion) {}
+
 fn benchmark_initialization_isolated(c: &mut Criterion) {
     let mut group = c.benchmark_group("initialization_only");
     
@@ -158,12 +213,12 @@ fn benchmark_initialization_isolated(c: &mut Criterion) {
     let map_path = map_file.to_str().unwrap();
     
     // Parse map once
-    let config = SimulationConfig {
-        num_ants: 10,
-        map_file: map_path.to_string(),
-        max_moves: 10000,
-        seed: Some(42),
-    };
+    let config = SimulationConfig::builder()
+        .ants(10)
+        .map_file(map_path)
+        .max_moves(10000)
+        .seed(Some(42))
+        .build();
     
     for num_ants in [10, 100, 1000].iter() {
         group.bench_with_input(
@@ -177,7 +232,8 @@ fn benchmark_initialization_isolated(c: &mut Criterion) {
                         sim
                     },
                     |mut sim| {
-                        black_box(sim.initialize_ants(num_ants));
+                        sim.initialize_ants(num_ants).unwrap();
+                        black_box(&sim);
                     },
                     BatchSize::SmallInput,
                 );
@@ -202,22 +258,22 @@ fn benchmark_scaling(c: &mut Criterion) {
             BenchmarkId::new("ants", num_ants),
             num_ants,
             |b, &num_ants| {
-                let config = SimulationConfig {
-                    num_ants,
-                    map_file: map_path.to_string(),
-                    max_moves: 1000, // Reduced for scaling test
-                    seed: Some(42),
-                };
+                let config = SimulationConfig::builder()
+                    .ants(num_ants)
+                    .map_file(map_path)
+                    .max_moves(1000) // Reduced for scaling test
+                    .seed(Some(42))
+                    .build();
                 
                 b.iter_batched(
                     || {
                         let mut sim = Simulation::new(config.clone());
                         parser::parse_map_file(&mut sim, map_path).unwrap();
-                        sim.initialize_ants(num_ants);
+                        sim.initialize_ants(num_ants).unwrap();
                         sim
                     },
                     |mut sim| {
-                        black_box(sim.run_simulation());
+                        black_box(sim.run_simulation())
                     },
                     BatchSize::SmallInput,
                 );
@@ -242,19 +298,19 @@ fn benchmark_random_seed_variance(c: &mut Criterion) {
         b.iter_batched(
             || {
                 seed_counter += 1;
-                let config = SimulationConfig {
-                    num_ants: 100,
-                    map_file: map_path.to_string(),
-                    max_moves: 1000,
-                    seed: Some(seed_counter), // Different seed each time
-                };
+                let config = SimulationConfig::builder()
+                    .ants(100)
+                    .map_file(map_path)
+                    .max_moves(1000)
+                    .seed(Some(seed_counter)) // Different seed each time
+                    .build();
                 let mut sim = Simulation::new(config);
                 parser::parse_map_file(&mut sim, map_path).unwrap();
-                sim.initialize_ants(100);
+                sim.initialize_ants(100).unwrap();
                 sim
             },
             |mut sim| {
-                black_box(sim.run_simulation());
+                black_box(sim.run_simulation())
             },
             BatchSize::SmallInput,
         );
@@ -268,6 +324,7 @@ criterion_group!(
     benches, 
     benchmark_simulation_hot_path,
     benchmark_parsing_isolated,
+    benchmark_parsing_mmap,
     benchmark_initialization_isolated,
     benchmark_scaling,
     benchmark_random_seed_variance
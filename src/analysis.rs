@@ -0,0 +1,242 @@
+use crate::types::{ColonyId, Direction};
+use crate::simulation::Simulation;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// Structural summary of the (surviving) colony graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapStats {
+    pub colonies: usize,                            // Valid colonies
+    pub total_edges: usize,                         // Undirected tunnels (symmetric pairs counted once)
+    pub isolated_colonies: usize,                   // Valid colonies with no usable tunnels
+    pub max_degree: u32,
+    pub avg_degree: f64,
+}
+
+/// Non-fatal map problem reported by `Simulation::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    AsymmetricEdge { from: String, to: String, direction: Direction },  // `to` has no tunnel back to `from`
+    SelfLoop { colony: String, direction: Direction },
+    IsolatedColony { colony: String },
+    DisconnectedComponent { colonies: Vec<String> },                    // Unreachable from the largest component
+}
+
+impl core::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationWarning::AsymmetricEdge { from, to, direction } => {
+                write!(f, "{} {:?} leads to {}, but {} has no tunnel back (expected {} {}={})",
+                       from, direction, to, to, to, direction.opposite().name(), from)
+            }
+            ValidationWarning::SelfLoop { colony, direction } => {
+                write!(f, "{} {:?} leads back to itself", colony, direction)
+            }
+            ValidationWarning::IsolatedColony { colony } => write!(f, "{} has no connections", colony),
+            ValidationWarning::DisconnectedComponent { colonies } => {
+                write!(f, "{} colonies are disconnected from the rest of the map: {}", colonies.len(), colonies.join(", "))
+            }
+        }
+    }
+}
+
+/// Read-only structural analysis of the colony graph (runs outside the hot path)
+impl Simulation {
+    /// Every directed tunnel between valid colonies as `(from, direction, to)`, by `from` id then
+    /// direction order (N, S, E, W, Up, Down)
+    pub fn edges(&self) -> impl Iterator<Item = (ColonyId, Direction, ColonyId)> + '_ {
+        (0..self.num_colonies)
+            .filter(move |&idx| self.colony_valid[idx])
+            .flat_map(move |idx| {
+                let from = idx as ColonyId;
+                self.valid_neighbors(from).map(move |(direction, to)| (from, direction, to))
+            })
+    }
+    
+    /// Like `edges`, but a tunnel with a way back is yielded once, from its lower-id end
+    /// (e.g. `A north=B` with `B south=A` only as A's north tunnel); one-way tunnels are all kept
+    pub fn undirected_edges(&self) -> impl Iterator<Item = (ColonyId, Direction, ColonyId)> + '_ {
+        self.edges().filter(move |&(from, _, to)| from <= to || self.directions_towards(to, from) == 0)
+    }
+    
+    /// Compute a structural summary of the map over valid colonies and tunnels between them
+    pub fn stats(&self) -> MapStats {
+        let mut colonies = 0;
+        let mut directed = 0;
+        let mut isolated_colonies = 0;
+        let mut max_degree = 0;
+        
+        for idx in 0..self.num_colonies {
+            if !self.colony_valid[idx] {
+                continue;
+            }
+            
+            colonies += 1;
+            let degree = self.colony_valid_dirs[idx].count_ones();
+            directed += degree as usize;
+            max_degree = max_degree.max(degree);
+            if degree == 0 {
+                isolated_colonies += 1;
+            }
+        }
+        let symmetric_directed = self.edges()
+            .filter(|&(from, _, to)| self.directions_towards(to, from) != 0)
+            .count();
+        
+        MapStats {
+            colonies,
+            total_edges: directed - symmetric_directed / 2,
+            isolated_colonies,
+            max_degree,
+            avg_degree: if colonies > 0 { directed as f64 / colonies as f64 } else { 0.0 },
+        }
+    }
+    
+    /// Total number of directed connections between valid colonies
+    pub fn total_connections(&self) -> usize {
+        (0..self.num_colonies)
+            .filter(|&idx| self.colony_valid[idx])
+            .map(|idx| self.colony_valid_dirs[idx].count_ones() as usize)
+            .sum()
+    }
+    
+    /// Valid colonies with no usable tunnels (ants spawning there are trapped)
+    pub fn isolated_colonies(&self) -> Vec<ColonyId> {
+        (0..self.num_colonies)
+            .filter(|&idx| self.colony_valid[idx] && self.colony_valid_dirs[idx] == 0)
+            .map(|idx| idx as ColonyId)
+            .collect()
+    }
+    
+    /// Number of valid colonies with each count of usable tunnels (index = degree, 0..=6)
+    pub fn degree_histogram(&self) -> [usize; 7] {
+        let mut histogram = [0; 7];
+        for idx in 0..self.num_colonies {
+            if self.colony_valid[idx] {
+                histogram[self.colony_valid_dirs[idx].count_ones() as usize] += 1;
+            }
+        }
+        histogram
+    }
+    
+    /// Each surviving colony's name with its number of tunnels to other surviving colonies, in id order
+    /// A degree of 0 marks a colony cut off by the destruction of all its neighbors
+    pub fn surviving_colony_degrees(&self) -> Vec<(String, u8)> {
+        (0..self.num_colonies)
+            .filter(|&idx| self.colony_valid[idx])
+            .map(|idx| (self.colony_names[idx].to_string(), self.colony_valid_dirs[idx].count_ones() as u8))
+            .collect()
+    }
+    
+    /// Group valid colonies into connected components, treating every tunnel as undirected
+    /// Each component is sorted by colony id, and components are ordered by their smallest id
+    pub fn connected_components(&self) -> Vec<Vec<ColonyId>> {
+        // Union-find over colony indices with path halving
+        let mut parent: Vec<usize> = (0..self.num_colonies).collect();
+        
+        fn find(parent: &mut [usize], mut idx: usize) -> usize {
+            while parent[idx] != idx {
+                parent[idx] = parent[parent[idx]];
+                idx = parent[idx];
+            }
+            idx
+        }
+        
+        for (from, _, to) in self.edges() {
+            let a = find(&mut parent, from as usize);
+            let b = find(&mut parent, to as usize);
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        
+        // Roots are the smallest index in each component, so scanning in order keeps groups sorted
+        let mut component_of_root = vec![usize::MAX; self.num_colonies];
+        let mut components: Vec<Vec<ColonyId>> = Vec::new();
+        for idx in 0..self.num_colonies {
+            if !self.colony_valid[idx] {
+                continue;
+            }
+            
+            let root = find(&mut parent, idx);
+            if component_of_root[root] == usize::MAX {
+                component_of_root[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[component_of_root[root]].push(idx as ColonyId);
+        }
+        
+        components
+    }
+    
+    /// Number of connected components (see `connected_components`) holding at least one living ant
+    /// Above 1, ants in different components can never meet, so some collisions are impossible
+    pub fn living_ant_components(&self) -> usize {
+        let mut component_of = vec![usize::MAX; self.num_colonies];
+        for (component_idx, component) in self.connected_components().iter().enumerate() {
+            for &colony_id in component {
+                component_of[colony_id as usize] = component_idx;
+            }
+        }
+        
+        let mut occupied = Vec::new();
+        for (_, colony_id) in self.living_ants() {
+            let component_idx = component_of[colony_id as usize];
+            if !occupied.contains(&component_idx) {
+                occupied.push(component_idx);
+            }
+        }
+        occupied.len()
+    }
+    
+    /// Run every structural sanity check over the built graph, collecting warnings instead of failing
+    /// Tunnels without a way back are reported unless marked one-way in the map or `config.allow_oneway` is set
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        
+        for (from, direction, to) in self.edges() {
+            if from == to {
+                warnings.push(ValidationWarning::SelfLoop {
+                    colony: self.colony_names[from as usize].to_string(),
+                    direction,
+                });
+            } else if !self.config.allow_oneway && !self.is_one_way(from, direction) && self.directions_towards(to, from) == 0 {
+                warnings.push(ValidationWarning::AsymmetricEdge {
+                    from: self.colony_names[from as usize].to_string(),
+                    to: self.colony_names[to as usize].to_string(),
+                    direction,
+                });
+            }
+        }
+        
+        for colony_id in self.isolated_colonies() {
+            warnings.push(ValidationWarning::IsolatedColony {
+                colony: self.colony_names[colony_id as usize].to_string(),
+            });
+        }
+        
+        // Every component other than the largest is unreachable from the bulk of the map
+        // (isolated colonies were already reported above)
+        let components = self.connected_components();
+        if components.len() > 1 {
+            let largest = components.iter()
+                .enumerate()
+                .max_by_key(|(i, component)| (component.len(), core::cmp::Reverse(*i)))
+                .map(|(i, _)| i)
+                .unwrap_or(0);
+            
+            for (i, component) in components.iter().enumerate() {
+                let isolated = component.len() == 1 && self.colony_valid_dirs[component[0] as usize] == 0;
+                if i == largest || isolated {
+                    continue;
+                }
+                
+                warnings.push(ValidationWarning::DisconnectedComponent {
+                    colonies: component.iter().map(|&id| self.colony_names[id as usize].to_string()).collect(),
+                });
+            }
+        }
+        
+        warnings
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// Compact bitset for per-ant and per-colony flags
+/// Stores one bit per entry (vs one byte for Vec<bool>) and supports word-at-a-time scans
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+const WORD_BITS: usize = 64;
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Create a bitset of `len` entries, all set to `value`
+    pub fn with_len(len: usize, value: bool) -> Self {
+        let mut bits = Self::new();
+        bits.resize(len, value);
+        bits
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    /// Resize to `new_len` entries, filling any new entries with `value`
+    pub fn resize(&mut self, new_len: usize, value: bool) {
+        if new_len > self.len {
+            self.words.resize(new_len.div_ceil(WORD_BITS), 0);
+            if value {
+                for idx in self.len..new_len {
+                    self.words[idx / WORD_BITS] |= 1 << (idx % WORD_BITS);
+                }
+            }
+        } else {
+            self.words.truncate(new_len.div_ceil(WORD_BITS));
+            // Keep bits past the end cleared so word-level scans stay correct
+            let tail = new_len % WORD_BITS;
+            if tail != 0 {
+                if let Some(last) = self.words.last_mut() {
+                    *last &= (1 << tail) - 1;
+                }
+            }
+        }
+        self.len = new_len;
+    }
+    
+    #[inline(always)]
+    pub fn get(&self, idx: usize) -> bool {
+        debug_assert!(idx < self.len);
+        (self.words[idx / WORD_BITS] >> (idx % WORD_BITS)) & 1 != 0
+    }
+    
+    #[inline(always)]
+    pub fn set(&mut self, idx: usize, value: bool) {
+        debug_assert!(idx < self.len);
+        let mask = 1 << (idx % WORD_BITS);
+        if value {
+            self.words[idx / WORD_BITS] |= mask;
+        } else {
+            self.words[idx / WORD_BITS] &= !mask;
+        }
+    }
+    
+    /// Check whether any bit is set, 64 entries at a time
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+    
+    /// Number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+    
+    /// Iterate over every entry as a bool, in index order
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(move |idx| self.get(idx))
+    }
+    
+    /// Heap memory used by the bit storage, in bytes
+    pub fn memory_bytes(&self) -> usize {
+        self.words.len() * core::mem::size_of::<u64>()
+    }
+}
+
+impl core::ops::Index<usize> for BitSet {
+    type Output = bool;
+    
+    #[inline(always)]
+    fn index(&self, idx: usize) -> &bool {
+        if self.get(idx) { &true } else { &false }
+    }
+}
@@ -0,0 +1,594 @@
+use crate::types::{ColonyId, AntId, CollisionRule, ColonySort, DestroyedTargetPolicy, SimulationConfig, SpawnMode};
+use crate::simulation::{MapMetadata, Simulation};
+use crate::bitset::BitSet;
+use crate::engine::{CaptureEvent, DeathCause, DestructionEvent};
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// Magic bytes and format version at the start of every checkpoint
+const MAGIC: &[u8; 4] = b"ANTS";
+const VERSION: u32 = 1;
+
+/// Checkpointing: hand-rolled little-endian binary format covering every simulation field
+impl Simulation {
+    /// Serialize the complete simulation state (graph, tombstones, ants, RNG and config)
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.bytes(MAGIC);
+        w.u32(VERSION);
+        
+        // Config (CLI-only flags live in `cli::CliOptions`; map-loading options, warmup and profiling aren't saved)
+        let config = &self.config;
+        w.u32(config.max_moves);
+        w.opt_u64(config.max_moves_range.map(|(min, _)| min as u64));
+        w.opt_u64(config.max_moves_range.map(|(_, max)| max as u64));
+        w.u32(config.max_iterations);
+        w.opt_u64(config.max_collisions.map(u64::from));
+        w.opt_u64(config.max_total_moves);
+        w.u64(config.num_ants as u64);
+        w.str(&config.map_file);
+        w.opt_u64(config.seed);
+        w.bool(config.no_backtrack);
+        for &weight in &config.direction_weights {
+            w.u32(weight);
+        }
+        w.bool(config.heatmap);
+        w.opt_u64(config.snapshot_every.map(u64::from));
+        w.opt_u64(config.oscillation_threshold.map(u64::from));
+        w.bool(config.death_causes);
+        w.bool(config.move_stats);
+        w.bool(config.destroy_on_collision);
+        w.bool(config.sort_collision_ants);
+        w.bool(config.shuffle_order);
+        w.u8(config.destroyed_target_policy as u8);
+        w.bool(config.settled_ants_block);
+        w.u8(config.collision_rule as u8);
+        w.u8(config.sort_colonies as u8);
+        w.bool(config.allow_oneway);
+        w.u64(config.initial_placements.len() as u64);
+        for (ant_id, colony_name) in &config.initial_placements {
+            w.u64(*ant_id as u64);
+            w.str(colony_name);
+        }
+        w.u64(config.spawn_colonies.len() as u64);
+        for colony_name in &config.spawn_colonies {
+            w.str(colony_name);
+        }
+        w.u8(config.spawn_mode as u8);
+        w.bool(config.spawn_collisions);
+        w.opt_u64(config.trace_ant.map(|ant_id| ant_id as u64));
+        // Written without `std` too, so checkpoints move between `std` and `no_std` builds
+        #[cfg(feature = "std")]
+        w.opt_u64(config.time_limit.map(|limit| limit.as_nanos() as u64));
+        #[cfg(not(feature = "std"))]
+        w.opt_u64(None);
+        
+        w.u32(self.iteration);
+        w.u64(self.total_collisions);
+        w.u64(self.captures.len() as u64);
+        for capture in &self.captures {
+            w.u32(capture.iteration);
+            w.u64(capture.colony as u64);
+            w.str(&capture.colony_name);
+            w.u64(capture.winner as u64);
+            w.u64(capture.loser as u64);
+        }
+        w.u64(self.spawn_destructions.len() as u64);
+        for event in &self.spawn_destructions {
+            w.u64(event.colony as u64);
+            w.str(&event.colony_name);
+            w.u64(event.ant_a as u64);
+            w.u64(event.ant_b as u64);
+        }
+        
+        // Colony graph
+        w.u64(self.num_colonies as u64);
+        w.bits(&self.colony_valid);
+        w.opt_ids(self.colony_destroyed_at.iter().map(|iteration| iteration.map(u64::from)), self.colony_destroyed_at.len());
+        for neighbors in [
+            &self.colony_north,
+            &self.colony_south,
+            &self.colony_east,
+            &self.colony_west,
+            &self.colony_up,
+            &self.colony_down,
+        ] {
+            w.opt_ids(neighbors.iter().map(|id| id.map(|id| id as u64)), neighbors.len());
+        }
+        w.u8s(&self.colony_valid_dirs);
+        w.u8s(&self.colony_one_way);
+        w.u64(self.colony_names.len() as u64);
+        for name in self.colony_names.iter() {
+            w.str(name);
+        }
+        w.bool(self.metadata.is_some());
+        if let Some(metadata) = &self.metadata {
+            let entries: Vec<(&str, &str)> = [("name", &metadata.name), ("version", &metadata.version)].into_iter()
+                .filter_map(|(key, value)| value.as_deref().map(|value| (key, value)))
+                .chain(metadata.fields.iter().map(|(key, value)| (key.as_str(), value.as_str())))
+                .collect();
+            w.u64(entries.len() as u64);
+            for (key, value) in entries {
+                w.str(key);
+                w.str(value);
+            }
+        }
+        
+        // Ants
+        w.u64(self.ant_colonies.len() as u64);
+        for &colony in &self.ant_colonies {
+            w.u64(colony as u64);
+        }
+        w.bits(&self.ant_alive);
+        w.u64(self.ant_moves.len() as u64);
+        for &moves in &self.ant_moves {
+            w.u32(moves);
+        }
+        w.u64(self.ant_max_moves.len() as u64);
+        for &limit in &self.ant_max_moves {
+            w.u32(limit);
+        }
+        w.opt_ids(self.ant_prev_colony.iter().map(|id| id.map(|id| id as u64)), self.ant_prev_colony.len());
+        w.u64(self.ant_death_cause.len() as u64);
+        for cause in &self.ant_death_cause {
+            w.death_cause(*cause);
+        }
+        w.u64(self.ant_oscillation_streak.len() as u64);
+        for &streak in &self.ant_oscillation_streak {
+            w.u32(streak);
+        }
+        w.u64(self.oscillations.len() as u64);
+        for &(ant_id, a, b) in &self.oscillations {
+            w.u64(ant_id as u64);
+            w.u64(a as u64);
+            w.u64(b as u64);
+        }
+        w.u64(self.living_count as u64);
+        w.u64(self.ants_at_max_moves as u64);
+        
+        // Collision tracking
+        w.u64(self.colony_ant_count.len() as u64);
+        for &count in &self.colony_ant_count {
+            w.u64(count as u64);
+        }
+        w.opt_ids(self.colony_first_ant.iter().map(|id| id.map(|id| id as u64)), self.colony_first_ant.len());
+        w.u8s(&self.colony_capacity);
+        
+        // Statistics
+        w.u64(self.colony_visits.len() as u64);
+        for &visits in &self.colony_visits {
+            w.u32(visits);
+        }
+        w.u64(self.occupancy_snapshots.len() as u64);
+        for (iteration, counts) in &self.occupancy_snapshots {
+            w.u32(*iteration);
+            w.u64(counts.len() as u64);
+            for &count in counts {
+                w.u64(count as u64);
+            }
+        }
+        w.u64(self.ant_trace.len() as u64);
+        for line in &self.ant_trace {
+            w.str(line);
+        }
+        
+        // RNG
+        w.u64(self.rng.state);
+        
+        w.buf
+    }
+    
+    /// Restore a simulation previously produced by `save_state`
+    /// Continuing a restored simulation yields the same results as never having stopped
+    pub fn load_state(bytes: &[u8]) -> Result<Simulation, String> {
+        let mut r = Reader { buf: bytes, pos: 0 };
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err("not a simulation checkpoint".to_string());
+        }
+        let version = r.u32()?;
+        if version != VERSION {
+            return Err(format!("unsupported checkpoint version {version}"));
+        }
+        
+        // Config
+        let mut config = SimulationConfig {
+            max_moves: r.u32()?,
+            max_moves_range: match (r.opt_u64()?, r.opt_u64()?) {
+                (Some(min), Some(max)) => Some((
+                    u32::try_from(min).map_err(|_| "move limit range overflows u32".to_string())?,
+                    u32::try_from(max).map_err(|_| "move limit range overflows u32".to_string())?,
+                )),
+                _ => None,
+            },
+            max_iterations: r.u32()?,
+            max_collisions: r.opt_u64()?
+                .map(|limit| u32::try_from(limit).map_err(|_| "collision limit overflows u32".to_string()))
+                .transpose()?,
+            max_total_moves: r.opt_u64()?,
+            num_ants: r.id()?,
+            map_file: r.string()?,
+            seed: r.opt_u64()?,
+            no_backtrack: r.bool()?,
+            ..SimulationConfig::default()
+        };
+        for weight in config.direction_weights.iter_mut() {
+            *weight = r.u32()?;
+        }
+        config.heatmap = r.bool()?;
+        config.snapshot_every = r.opt_u64()?
+            .map(|every| u32::try_from(every).map_err(|_| "snapshot interval overflows u32".to_string()))
+            .transpose()?;
+        config.oscillation_threshold = r.opt_u64()?
+            .map(|threshold| u32::try_from(threshold).map_err(|_| "oscillation threshold overflows u32".to_string()))
+            .transpose()?;
+        config.death_causes = r.bool()?;
+        config.move_stats = r.bool()?;
+        config.destroy_on_collision = r.bool()?;
+        config.sort_collision_ants = r.bool()?;
+        config.shuffle_order = r.bool()?;
+        config.destroyed_target_policy = match r.u8()? {
+            0 => DestroyedTargetPolicy::KillAnt,
+            1 => DestroyedTargetPolicy::StayPut,
+            other => return Err(format!("invalid destroyed-target policy {other} in checkpoint")),
+        };
+        config.settled_ants_block = r.bool()?;
+        config.collision_rule = match r.u8()? {
+            0 => CollisionRule::DestroyColony,
+            1 => CollisionRule::LastWins,
+            2 => CollisionRule::FirstWins,
+            other => return Err(format!("invalid collision rule {other} in checkpoint")),
+        };
+        config.sort_colonies = match r.u8()? {
+            0 => ColonySort::Line,
+            1 => ColonySort::Name,
+            2 => ColonySort::Degree,
+            other => return Err(format!("invalid colony sort {other} in checkpoint")),
+        };
+        config.allow_oneway = r.bool()?;
+        let num_placements = r.len()?;
+        config.initial_placements = (0..num_placements)
+            .map(|_| Ok((r.id()?, r.string()?)))
+            .collect::<Result<_, String>>()?;
+        let num_spawn_colonies = r.len()?;
+        config.spawn_colonies = (0..num_spawn_colonies).map(|_| r.string()).collect::<Result<_, _>>()?;
+        config.spawn_mode = match r.u8()? {
+            0 => SpawnMode::Random,
+            1 => SpawnMode::RoundRobin,
+            other => return Err(format!("invalid spawn mode {other} in checkpoint")),
+        };
+        config.spawn_collisions = r.bool()?;
+        config.trace_ant = r.opt_u64()?
+            .map(|ant_id| AntId::try_from(ant_id).map_err(|_| "traced ant id overflows".to_string()))
+            .transpose()?;
+        #[cfg(feature = "std")]
+        {
+            config.time_limit = r.opt_u64()?.map(Duration::from_nanos);
+        }
+        #[cfg(not(feature = "std"))]
+        if r.opt_u64()?.is_some() {
+            return Err("checkpoint has a time limit, which needs the `std` feature".to_string());
+        }
+        let mut sim = Simulation::new(config);
+        sim.iteration = r.u32()?;
+        sim.total_collisions = r.u64()?;
+        let num_captures = r.len()?;
+        sim.captures = (0..num_captures)
+            .map(|_| Ok(CaptureEvent {
+                iteration: r.u32()?,
+                colony: r.id()?,
+                colony_name: r.string()?,
+                winner: r.id()?,
+                loser: r.id()?,
+            }))
+            .collect::<Result<_, String>>()?;
+        let num_spawn_destructions = r.len()?;
+        sim.spawn_destructions = (0..num_spawn_destructions)
+            .map(|_| Ok(DestructionEvent {
+                iteration: 0,
+                colony: r.id()?,
+                colony_name: r.string()?,
+                ant_a: r.id()?,
+                ant_b: r.id()?,
+            }))
+            .collect::<Result<_, String>>()?;
+        
+        // Colony graph
+        sim.num_colonies = r.len()?;
+        sim.colony_valid = r.bits()?;
+        sim.colony_destroyed_at = r.opt_ids()?;
+        sim.colony_north = r.opt_ids()?;
+        sim.colony_south = r.opt_ids()?;
+        sim.colony_east = r.opt_ids()?;
+        sim.colony_west = r.opt_ids()?;
+        sim.colony_up = r.opt_ids()?;
+        sim.colony_down = r.opt_ids()?;
+        sim.colony_valid_dirs = r.u8s()?;
+        sim.colony_one_way = r.u8s()?;
+        let num_names = r.len()?;
+        sim.colony_names.resize(num_names);
+        for colony_id in 0..num_names {
+            sim.colony_names.set(colony_id as ColonyId, &r.string()?);
+        }
+        if r.bool()? {
+            let mut metadata = MapMetadata::default();
+            for _ in 0..r.len()? {
+                metadata.insert(&r.string()?, &r.string()?);
+            }
+            sim.metadata = Some(metadata);
+        }
+        
+        // Ants
+        let num_ants = r.len()?;
+        sim.ant_colonies = (0..num_ants).map(|_| r.id()).collect::<Result<_, _>>()?;
+        sim.ant_alive = r.bits()?;
+        let num_moves = r.len()?;
+        sim.ant_moves = (0..num_moves).map(|_| r.u32()).collect::<Result<_, _>>()?;
+        let num_limits = r.len()?;
+        sim.ant_max_moves = (0..num_limits).map(|_| r.u32()).collect::<Result<_, _>>()?;
+        sim.ant_prev_colony = r.opt_ids()?;
+        let num_causes = r.len()?;
+        sim.ant_death_cause = (0..num_causes).map(|_| r.death_cause()).collect::<Result<_, _>>()?;
+        let num_streaks = r.len()?;
+        sim.ant_oscillation_streak = (0..num_streaks).map(|_| r.u32()).collect::<Result<_, _>>()?;
+        let num_oscillations = r.len()?;
+        sim.oscillations = (0..num_oscillations)
+            .map(|_| Ok((r.id()?, r.id()?, r.id()?)))
+            .collect::<Result<_, String>>()?;
+        sim.living_count = r.len()?;
+        sim.ants_at_max_moves = r.len()?;
+        
+        // Collision tracking
+        let num_counts = r.len()?;
+        sim.colony_ant_count = (0..num_counts).map(|_| r.id()).collect::<Result<_, _>>()?;
+        sim.colony_first_ant = r.opt_ids::<AntId>()?;
+        sim.colony_capacity = r.u8s()?;
+        
+        // Statistics
+        let num_visits = r.len()?;
+        sim.colony_visits = (0..num_visits).map(|_| r.u32()).collect::<Result<_, _>>()?;
+        let num_snapshots = r.len()?;
+        sim.occupancy_snapshots = (0..num_snapshots)
+            .map(|_| {
+                let iteration = r.u32()?;
+                let num_counts = r.len()?;
+                Ok((iteration, (0..num_counts).map(|_| r.id()).collect::<Result<_, _>>()?))
+            })
+            .collect::<Result<_, String>>()?;
+        let num_trace_lines = r.len()?;
+        sim.ant_trace = (0..num_trace_lines).map(|_| r.string()).collect::<Result<_, _>>()?;
+        
+        // RNG
+        sim.rng.state = r.u64()?;
+        
+        if r.pos != bytes.len() {
+            return Err("trailing bytes after checkpoint".to_string());
+        }
+        
+        let colony_vectors_consistent = [
+            sim.colony_valid.len(),
+            sim.colony_destroyed_at.len(),
+            sim.colony_north.len(),
+            sim.colony_south.len(),
+            sim.colony_east.len(),
+            sim.colony_west.len(),
+            sim.colony_up.len(),
+            sim.colony_down.len(),
+            sim.colony_valid_dirs.len(),
+            sim.colony_one_way.len(),
+            sim.colony_names.len(),
+            sim.colony_ant_count.len(),
+            sim.colony_first_ant.len(),
+            sim.colony_capacity.len(),
+            sim.colony_visits.len(),
+        ].iter().all(|&len| len == sim.num_colonies)
+            && sim.occupancy_snapshots.iter().all(|(_, counts)| counts.len() == sim.num_colonies);
+        let ant_vectors_consistent = [
+            sim.ant_alive.len(),
+            sim.ant_moves.len(),
+            sim.ant_max_moves.len(),
+            sim.ant_prev_colony.len(),
+            sim.ant_death_cause.len(),
+            sim.ant_oscillation_streak.len(),
+        ].iter().all(|&len| len == sim.ant_colonies.len());
+        if !colony_vectors_consistent || !ant_vectors_consistent {
+            return Err("checkpoint has inconsistent vector lengths".to_string());
+        }
+        
+        Ok(sim)
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+    
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+    
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+    
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+    
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+    
+    fn str(&mut self, value: &str) {
+        self.u64(value.len() as u64);
+        self.bytes(value.as_bytes());
+    }
+    
+    fn opt_u64(&mut self, value: Option<u64>) {
+        match value {
+            Some(v) => {
+                self.u8(1);
+                self.u64(v);
+            }
+            None => self.u8(0),
+        }
+    }
+    
+    fn u8s(&mut self, values: &[u8]) {
+        self.u64(values.len() as u64);
+        self.bytes(values);
+    }
+    
+    /// Ids are widened to u64 so checkpoints don't depend on the `ids-u32` feature
+    fn opt_ids(&mut self, ids: impl Iterator<Item = Option<u64>>, len: usize) {
+        self.u64(len as u64);
+        for id in ids {
+            self.opt_u64(id);
+        }
+    }
+    
+    /// Tag byte (0 = alive) followed by the colony id
+    fn death_cause(&mut self, cause: Option<DeathCause>) {
+        let (tag, colony) = match cause {
+            None => return self.u8(0),
+            Some(DeathCause::Collision(colony)) => (1, colony),
+            Some(DeathCause::EnteredDestroyedColony(colony)) => (2, colony),
+            Some(DeathCause::ColonyDestroyedUnderneath(colony)) => (3, colony),
+            Some(DeathCause::LostCapture(colony)) => (4, colony),
+        };
+        self.u8(tag);
+        self.u64(colony as u64);
+    }
+    
+    /// Bits packed eight per byte, preceded by the entry count
+    fn bits(&mut self, bits: &BitSet) {
+        self.u64(bits.len() as u64);
+        let mut byte = 0u8;
+        for (i, bit) in bits.iter().enumerate() {
+            byte |= (bit as u8) << (i % 8);
+            if i % 8 == 7 {
+                self.u8(byte);
+                byte = 0;
+            }
+        }
+        if !bits.len().is_multiple_of(8) {
+            self.u8(byte);
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n)
+            .filter(|&end| end <= self.buf.len())
+            .ok_or_else(|| "checkpoint truncated".to_string())?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+    
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+    
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+    
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+    
+    fn len(&mut self) -> Result<usize, String> {
+        usize::try_from(self.u64()?).map_err(|_| "checkpoint length overflows usize".to_string())
+    }
+    
+    fn bool(&mut self) -> Result<bool, String> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(format!("invalid bool byte {other} in checkpoint")),
+        }
+    }
+    
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| "invalid UTF-8 in checkpoint".to_string())
+    }
+    
+    fn opt_u64(&mut self) -> Result<Option<u64>, String> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(self.u64()?)),
+            other => Err(format!("invalid option tag {other} in checkpoint")),
+        }
+    }
+    
+    fn u8s(&mut self) -> Result<Vec<u8>, String> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+    
+    /// Narrow a stored id back to the compiled id width, rejecting values that don't fit
+    fn id<T: TryFrom<u64>>(&mut self) -> Result<T, String> {
+        let value = self.u64()?;
+        T::try_from(value).map_err(|_| format!("id {value} does not fit this build's id width"))
+    }
+    
+    fn opt_ids<T: TryFrom<u64>>(&mut self) -> Result<Vec<Option<T>>, String> {
+        let len = self.len()?;
+        (0..len)
+            .map(|_| match self.opt_u64()? {
+                Some(value) => T::try_from(value)
+                    .map(Some)
+                    .map_err(|_| format!("id {value} does not fit this build's id width")),
+                None => Ok(None),
+            })
+            .collect()
+    }
+    
+    fn death_cause(&mut self) -> Result<Option<DeathCause>, String> {
+        let tag = self.u8()?;
+        if tag == 0 {
+            return Ok(None);
+        }
+        
+        let colony = self.id::<ColonyId>()?;
+        match tag {
+            1 => Ok(Some(DeathCause::Collision(colony))),
+            2 => Ok(Some(DeathCause::EnteredDestroyedColony(colony))),
+            3 => Ok(Some(DeathCause::ColonyDestroyedUnderneath(colony))),
+            4 => Ok(Some(DeathCause::LostCapture(colony))),
+            other => Err(format!("invalid death cause tag {other} in checkpoint")),
+        }
+    }
+    
+    fn bits(&mut self) -> Result<BitSet, String> {
+        let len = self.len()?;
+        let bytes = self.take(len.div_ceil(8))?;
+        let mut bits = BitSet::with_len(len, false);
+        for i in 0..len {
+            bits.set(i, (bytes[i / 8] >> (i % 8)) & 1 != 0);
+        }
+        Ok(bits)
+    }
+}
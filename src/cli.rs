@@ -1,14 +1,67 @@
 use std::env;
-use crate::types::SimulationConfig;
+use std::time::Duration;
+use crate::output;
+use crate::simulation::deterministic_seed;
+use crate::types::{AntId, ColorChoice, Direction, OutputFormat, PerfThresholds, SimulationConfig, UNLIMITED_MOVES};
 
-const MAX_MOVES: u16 = 10_000;
+const MAX_MOVES: u32 = 10_000;
+
+/// Options that only shape what the binary prints or which run mode it picks; everything that
+/// changes the simulation itself is in `SimulationConfig`
+#[derive(Debug, Clone, Default)]
+pub struct CliOptions {
+    pub check_connectivity: bool,               // Warn when the parsed map splits into several components
+    pub dry_run: bool,                          // Parse and summarize the map without simulating
+    pub warn_isolated: bool,                    // List colonies with zero connections on stderr
+    pub output_format: OutputFormat,
+    pub repeat: u32,                            // Run this many times with consecutive seeds and aggregate (0 = single run)
+    pub verbose: bool,                          // Print per-run detail in repeated runs
+    pub message_format: Option<String>,         // Template for destruction messages (None = the default wording)
+    pub echo_map: bool,                         // Print the parsed map in canonical form before simulating
+    pub degree_histogram: bool,                 // Print how many colonies have each number of connections
+    pub record_path: Option<String>,            // Write a replay log of every move to this file
+    pub perf_thresholds: Option<PerfThresholds>, // Judge the runtime against these targets (None = timings only)
+    pub seed_list: Vec<u64>,                    // Run once per seed (from `--seed-file`) with one summary line each
+    pub ascii_grid: Option<usize>,              // Print the post-run map as a grid this many colonies wide
+    pub validate_only: bool,                    // Parse and validate silently; problems go to stderr with exit code 1
+    pub interactive: bool,                      // Step the simulation by commands read from stdin
+    pub snapshot_path: Option<String>,          // Write the occupancy snapshots to this file (`.json` = JSON, else CSV)
+    pub require_connected: bool,                // Warn when the placed ants don't all share one component
+    pub color: ColorChoice,                     // Colors in text results only (never in CSV or counts)
+}
 
 #[derive(Debug)]
 pub enum ParseError {
     InvalidUsage(String),
     InvalidAntCount(String),
+    TooManyAnts(u64),
     FileNotFound(String),
     InvalidSeed(String),
+    InvalidBias(String),
+    InvalidMaxMoves(String),
+    InvalidMaxIterations(String),
+    InvalidTimeLimit(String),
+    InvalidMessageFormat(String),
+    InvalidColonySort(String),
+    InvalidSpawnIn(String),
+    InvalidSpawnMode(String),
+    InvalidColor(String),
+    InvalidCollisionRule(String),
+    InvalidOscillationThreshold(String),
+    InvalidFormat(String),
+    InvalidPlacement(String),
+    InvalidRepeat(String),
+    InvalidDensity(String),
+    InvalidMaxCollisions(String),
+    InvalidMaxTotalMoves(String),
+    InvalidWarmup(String),
+    InvalidTraceAnt(String),
+    InvalidPerfTarget(String),
+    InvalidSeedFile(String),
+    InvalidMaxMovesRange(String),
+    InvalidDestroyedTarget(String),
+    InvalidAsciiGrid(String),
+    InvalidSnapshotEvery(String),
 }
 
 impl std::fmt::Display for ParseError {
@@ -16,45 +69,387 @@ impl std::fmt::Display for ParseError {
         match self {
             ParseError::InvalidUsage(msg) => write!(f, "Usage error: {}", msg),
             ParseError::InvalidAntCount(val) => write!(f, "Invalid number of ants: {}", val),
+            ParseError::TooManyAnts(n) => write!(f, "{} ants exceeds maximum {}", n, AntId::MAX),
             ParseError::FileNotFound(path) => write!(f, "Map file does not exist: {}", path),
             ParseError::InvalidSeed(val) => write!(f, "Invalid seed: {}", val),
+            ParseError::InvalidBias(val) => write!(f, "Invalid direction bias: {}", val),
+            ParseError::InvalidMaxMoves(val) => write!(f, "Invalid max moves: {}", val),
+            ParseError::InvalidMaxIterations(val) => write!(f, "Invalid max iterations: {} (expected a positive integer)", val),
+            ParseError::InvalidTimeLimit(val) => write!(f, "Invalid time limit: {} (expected seconds > 0)", val),
+            ParseError::InvalidMessageFormat(msg) => write!(f, "Invalid message format: {} (placeholders: {{colony}} {{ant_a}} {{ant_b}} {{iteration}})", msg),
+            ParseError::InvalidColonySort(val) => write!(f, "Invalid colony sort: {} (expected name, line or degree)", val),
+            ParseError::InvalidSpawnIn(val) => write!(f, "Invalid spawn colonies: {} (expected NAME,NAME,...)", val),
+            ParseError::InvalidSpawnMode(val) => write!(f, "Invalid spawn mode: {} (expected random or round-robin)", val),
+            ParseError::InvalidColor(val) => write!(f, "Invalid color choice: {} (expected auto, always or never)", val),
+            ParseError::InvalidCollisionRule(val) => write!(f, "Invalid collision rule: {} (expected destroy, last-wins or first-wins)", val),
+            ParseError::InvalidOscillationThreshold(val) => write!(f, "Invalid oscillation threshold: {} (expected a positive integer)", val),
+            ParseError::InvalidPlacement(val) => write!(f, "Invalid placement: {} (expected antN=Colony)", val),
+            ParseError::InvalidRepeat(val) => write!(f, "Invalid repeat count: {}", val),
+            ParseError::InvalidMaxCollisions(val) => write!(f, "Invalid max collisions: {} (expected a positive integer)", val),
+            ParseError::InvalidMaxTotalMoves(val) => write!(f, "Invalid max total moves: {} (expected a positive integer)", val),
+            ParseError::InvalidTraceAnt(val) => write!(f, "Invalid ant to trace: {} (expected an ant id)", val),
+            ParseError::InvalidWarmup(val) => write!(f, "Invalid warmup: {} (expected a non-negative integer)", val),
+            ParseError::InvalidPerfTarget(val) => write!(f, "Invalid performance target: {} (expected milliseconds > 0)", val),
+            ParseError::InvalidSeedFile(msg) => write!(f, "Invalid seed file: {}", msg),
+            ParseError::InvalidMaxMovesRange(val) => write!(f, "Invalid move limit range: {} (expected MIN:MAX with MIN <= MAX)", val),
+            ParseError::InvalidDestroyedTarget(val) => write!(f, "Invalid destroyed-target policy: {} (expected kill or stay)", val),
+            ParseError::InvalidAsciiGrid(val) => write!(f, "Invalid grid width: {} (expected a positive integer)", val),
+            ParseError::InvalidSnapshotEvery(val) => write!(f, "Invalid snapshot interval: {} (expected a positive number of iterations)", val),
+            ParseError::InvalidDensity(val) => write!(f, "Invalid density: {} (expected ants per colony > 0)", val),
+            ParseError::InvalidFormat(val) => write!(f, "Invalid output format: {} (expected text, csv or counts)", val),
         }
     }
 }
 
 impl std::error::Error for ParseError {}
 
-pub fn parse_args() -> Result<SimulationConfig, ParseError> {
+pub fn parse_args() -> Result<(SimulationConfig, CliOptions), ParseError> {
     let args: Vec<String> = env::args().collect();
     
     if args.len() < 3 {
         return Err(ParseError::InvalidUsage(
-            format!("Usage: {} <num_ants> <map_file> [--seed N]", args[0])
+            format!("Usage: {} <num_ants> <map_file> | <map_file> --density K | <map_file> --validate-only [--seed N] [--max-moves N] [--no-move-limit] [--max-moves-range MIN:MAX] [--max-iterations N] [--time-limit SECONDS] [--max-collisions N] [--max-total-moves N] [--warmup N] [--no-backtrack] [--bias dir=W,...] [--check-connectivity] [--require-connected] [--heatmap] [--no-destroy] [--sort-collision-ants] [--shuffle-order] [--death-report] [--trace-ant ID] [--move-stats] [--detect-oscillation N] [--collision-rule destroy|last-wins|first-wins] [--destroyed-target kill|stay] [--place antN=Colony,...] [--spawn-in NAME,...] [--spawn-mode random|round-robin] [--spawn-collisions] [--repeat N] [--seed-file PATH] [--verbose] [--perf] [--perf-target MS] [--profile] [--record PATH] [--merge-map PATH] [--echo-map] [--dry-run] [--interactive] [--validate-only] [--allow-oneway] [--format text|csv|counts] [--count-only] [--color auto|always|never] [--message-format TEMPLATE] [--sort-colonies name|line|degree] [--warn-isolated] [--degree-histogram] [--ascii-grid COLS] [--snapshot-every N] [--snapshot-file PATH] [--deterministic]", args[0])
         ));
     }
     
-    let num_ants = args[1].parse().map_err(|_| {
-        ParseError::InvalidAntCount(args[1].clone())
-    })?;
-    
-    let map_file = args[2].clone();
+    // `--density` replaces the positional ant count, so the map file comes first
+    let density_mode = args.iter().any(|arg| arg == "--density");
+    let (num_ants, map_file, first_flag) = if density_mode {
+        if !args[2].starts_with("--") {
+            return Err(ParseError::InvalidUsage("--density cannot be combined with a positional ant count".to_string()));
+        }
+        (0, args[1].clone(), 2)
+    } else if args[2] == "--validate-only" {
+        // Validation doesn't need an ant count either
+        (0, args[1].clone(), 2)
+    } else {
+        // Parse wide first so an oversized count is reported as such rather than as garbage
+        let requested_ants: u64 = args[1].parse().map_err(|_| {
+            ParseError::InvalidAntCount(args[1].clone())
+        })?;
+        let num_ants = AntId::try_from(requested_ants)
+            .map_err(|_| ParseError::TooManyAnts(requested_ants))?;
+        (num_ants, args[2].clone(), 3)
+    };
     
     if !std::path::Path::new(&map_file).exists() {
         return Err(ParseError::FileNotFound(map_file));
     }
     
-    let seed = if args.len() > 3 && args[3] == "--seed" && args.len() > 4 {
-        Some(args[4].parse().map_err(|_| {
-            ParseError::InvalidSeed(args[4].clone())
-        })?)
-    } else {
-        None
-    };
-    
-    Ok(SimulationConfig {
+    let mut config = SimulationConfig {
         num_ants,
         map_file,
         max_moves: MAX_MOVES,
-        seed,
-    })
+        ..SimulationConfig::default()
+    };
+    let mut options = CliOptions::default();
+    
+    // Optional flags after the positional arguments
+    let mut deterministic = false;
+    let mut i = first_flag;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--seed" => {
+                let value = flag_value(&args, i)?;
+                config.seed = Some(value.parse().map_err(|_| {
+                    ParseError::InvalidSeed(value.to_string())
+                })?);
+                i += 1;
+            }
+            "--max-moves" => {
+                let value = flag_value(&args, i)?;
+                config.max_moves = match value.parse().map_err(|_| ParseError::InvalidMaxMoves(value.to_string()))? {
+                    0 => UNLIMITED_MOVES,
+                    max_moves => max_moves,
+                };
+                i += 1;
+            }
+            "--no-move-limit" => config.max_moves = UNLIMITED_MOVES,
+            "--max-moves-range" => {
+                let value = flag_value(&args, i)?;
+                let invalid = || ParseError::InvalidMaxMovesRange(value.to_string());
+                let (min, max) = value.split_once(':').ok_or_else(invalid)?;
+                let min: u32 = min.parse().map_err(|_| invalid())?;
+                let max: u32 = max.parse().map_err(|_| invalid())?;
+                if min > max {
+                    return Err(invalid());
+                }
+                config.max_moves_range = Some((min, max));
+                i += 1;
+            }
+            "--max-iterations" => {
+                let value = flag_value(&args, i)?;
+                config.max_iterations = value.parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| ParseError::InvalidMaxIterations(value.to_string()))?;
+                i += 1;
+            }
+            "--max-collisions" => {
+                let value = flag_value(&args, i)?;
+                config.max_collisions = Some(value.parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| ParseError::InvalidMaxCollisions(value.to_string()))?);
+                i += 1;
+            }
+            "--max-total-moves" => {
+                let value = flag_value(&args, i)?;
+                config.max_total_moves = Some(value.parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| ParseError::InvalidMaxTotalMoves(value.to_string()))?);
+                i += 1;
+            }
+            "--warmup" => {
+                let value = flag_value(&args, i)?;
+                config.warmup = value.parse()
+                    .map_err(|_| ParseError::InvalidWarmup(value.to_string()))?;
+                i += 1;
+            }
+            "--time-limit" => {
+                let value = flag_value(&args, i)?;
+                let seconds: f64 = value.parse()
+                    .ok()
+                    .filter(|&secs: &f64| secs > 0.0)
+                    .ok_or_else(|| ParseError::InvalidTimeLimit(value.to_string()))?;
+                config.time_limit = Some(Duration::try_from_secs_f64(seconds)
+                    .map_err(|_| ParseError::InvalidTimeLimit(value.to_string()))?);
+                i += 1;
+            }
+            "--no-backtrack" => config.no_backtrack = true,
+            "--check-connectivity" => options.check_connectivity = true,
+            "--require-connected" => options.require_connected = true,
+            "--heatmap" => config.heatmap = true,
+            "--no-destroy" => config.destroy_on_collision = false,
+            "--sort-collision-ants" => config.sort_collision_ants = true,
+            "--shuffle-order" => config.shuffle_order = true,
+            "--death-report" => config.death_causes = true,
+            "--trace-ant" => {
+                let value = flag_value(&args, i)?;
+                config.trace_ant = Some(value.parse()
+                    .map_err(|_| ParseError::InvalidTraceAnt(value.to_string()))?);
+                i += 1;
+            }
+            "--move-stats" => config.move_stats = true,
+            "--detect-oscillation" => {
+                let value = flag_value(&args, i)?;
+                config.oscillation_threshold = Some(value.parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| ParseError::InvalidOscillationThreshold(value.to_string()))?);
+                i += 1;
+            }
+            "--collision-rule" => {
+                let value = flag_value(&args, i)?;
+                config.collision_rule = value.parse()
+                    .map_err(|_| ParseError::InvalidCollisionRule(value.to_string()))?;
+                i += 1;
+            }
+            "--repeat" => {
+                let value = flag_value(&args, i)?;
+                options.repeat = value.parse().map_err(|_| {
+                    ParseError::InvalidRepeat(value.to_string())
+                })?;
+                i += 1;
+            }
+            "--density" => {
+                let value = flag_value(&args, i)?;
+                config.ant_density = Some(value.parse()
+                    .ok()
+                    .filter(|&k: &f64| k > 0.0 && k.is_finite())
+                    .ok_or_else(|| ParseError::InvalidDensity(value.to_string()))?);
+                i += 1;
+            }
+            "--seed-file" => {
+                options.seed_list = read_seed_file(flag_value(&args, i)?)?;
+                i += 1;
+            }
+            "--verbose" => options.verbose = true,
+            "--profile" => config.profile = true,
+            "--perf" => {
+                options.perf_thresholds.get_or_insert_with(PerfThresholds::default);
+            }
+            "--perf-target" => {
+                let value = flag_value(&args, i)?;
+                let millis: f64 = value.parse()
+                    .ok()
+                    .filter(|&ms: &f64| ms > 0.0)
+                    .ok_or_else(|| ParseError::InvalidPerfTarget(value.to_string()))?;
+                let target = Duration::try_from_secs_f64(millis / 1000.0)
+                    .map_err(|_| ParseError::InvalidPerfTarget(value.to_string()))?;
+                options.perf_thresholds = Some(PerfThresholds::uniform(target));
+                i += 1;
+            }
+            "--record" => {
+                options.record_path = Some(flag_value(&args, i)?.to_string());
+                i += 1;
+            }
+            "--destroyed-target" => {
+                let value = flag_value(&args, i)?;
+                config.destroyed_target_policy = value.parse()
+                    .map_err(|_| ParseError::InvalidDestroyedTarget(value.to_string()))?;
+                i += 1;
+            }
+            "--place" => {
+                config.initial_placements.extend(parse_placements(flag_value(&args, i)?)?);
+                i += 1;
+            }
+            "--spawn-in" => {
+                let value = flag_value(&args, i)?;
+                if value.split(',').any(str::is_empty) {
+                    return Err(ParseError::InvalidSpawnIn(value.to_string()));
+                }
+                config.spawn_colonies.extend(value.split(',').map(str::to_string));
+                i += 1;
+            }
+            "--spawn-collisions" => config.spawn_collisions = true,
+            "--spawn-mode" => {
+                let value = flag_value(&args, i)?;
+                config.spawn_mode = value.parse()
+                    .map_err(|_| ParseError::InvalidSpawnMode(value.to_string()))?;
+                i += 1;
+            }
+            "--merge-map" => {
+                let value = flag_value(&args, i)?;
+                if !std::path::Path::new(value).exists() {
+                    return Err(ParseError::FileNotFound(value.to_string()));
+                }
+                config.extra_map_files.push(value.to_string());
+                i += 1;
+            }
+            "--echo-map" => options.echo_map = true,
+            "--dry-run" => options.dry_run = true,
+            "--interactive" => options.interactive = true,
+            "--validate-only" => options.validate_only = true,
+            "--allow-oneway" => config.allow_oneway = true,
+            "--count-only" => options.output_format = OutputFormat::Counts,
+            "--format" => {
+                let value = flag_value(&args, i)?;
+                options.output_format = value.parse::<OutputFormat>()
+                    .map_err(|_| ParseError::InvalidFormat(value.to_string()))?;
+                i += 1;
+            }
+            "--color" => {
+                let value = flag_value(&args, i)?;
+                options.color = value.parse()
+                    .map_err(|_| ParseError::InvalidColor(value.to_string()))?;
+                i += 1;
+            }
+            "--sort-colonies" => {
+                let value = flag_value(&args, i)?;
+                config.sort_colonies = value.parse()
+                    .map_err(|_| ParseError::InvalidColonySort(value.to_string()))?;
+                i += 1;
+            }
+            "--message-format" => {
+                let value = flag_value(&args, i)?;
+                output::validate_message_format(value).map_err(ParseError::InvalidMessageFormat)?;
+                options.message_format = Some(value.to_string());
+                i += 1;
+            }
+            "--warn-isolated" => options.warn_isolated = true,
+            "--degree-histogram" => options.degree_histogram = true,
+            "--ascii-grid" => {
+                let value = flag_value(&args, i)?;
+                options.ascii_grid = Some(value.parse()
+                    .ok()
+                    .filter(|&cols| cols > 0)
+                    .ok_or_else(|| ParseError::InvalidAsciiGrid(value.to_string()))?);
+                i += 1;
+            }
+            "--snapshot-every" => {
+                let value = flag_value(&args, i)?;
+                config.snapshot_every = Some(value.parse()
+                    .ok()
+                    .filter(|&every| every > 0)
+                    .ok_or_else(|| ParseError::InvalidSnapshotEvery(value.to_string()))?);
+                i += 1;
+            }
+            "--snapshot-file" => {
+                options.snapshot_path = Some(flag_value(&args, i)?.to_string());
+                i += 1;
+            }
+            "--deterministic" => deterministic = true,
+            "--bias" => {
+                config.direction_weights = parse_bias(flag_value(&args, i)?)?;
+                i += 1;
+            }
+            other => {
+                return Err(ParseError::InvalidUsage(format!("Unknown option: {other}")));
+            }
+        }
+        i += 1;
+    }
+    
+    // Without an explicit seed, derive one from the map contents so the same inputs always reproduce
+    if deterministic && config.seed.is_none() {
+        let mut contents = Vec::new();
+        for path in std::iter::once(&config.map_file).chain(&config.extra_map_files) {
+            contents.extend(std::fs::read(path).map_err(|_| ParseError::FileNotFound(path.clone()))?);
+        }
+        config.seed = Some(deterministic_seed(&contents, config.num_ants));
+    }
+    
+    Ok((config, options))
+}
+
+/// Get the value following the flag at `i`
+fn flag_value(args: &[String], i: usize) -> Result<&str, ParseError> {
+    args.get(i + 1)
+        .map(String::as_str)
+        .ok_or_else(|| ParseError::InvalidUsage(format!("Missing value for {}", args[i])))
+}
+
+/// Read one u64 seed per line, skipping blank lines and `#` comments
+fn read_seed_file(path: &str) -> Result<Vec<u64>, ParseError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| ParseError::InvalidSeedFile(format!("{path}: {e}")))?;
+    
+    let mut seeds = Vec::new();
+    for (line_idx, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        seeds.push(line.parse().map_err(|_| {
+            ParseError::InvalidSeedFile(format!("{path}: line {}: not a seed: {line}", line_idx + 1))
+        })?);
+    }
+    
+    if seeds.is_empty() {
+        return Err(ParseError::InvalidSeedFile(format!("{path}: no seeds")));
+    }
+    Ok(seeds)
+}
+
+/// Parse `north=2,east=3` into per-direction weights; unlisted directions keep weight 1
+fn parse_bias(spec: &str) -> Result<[u32; 6], ParseError> {
+    let mut weights = [1; 6];
+    
+    for entry in spec.split(',') {
+        let (dir_str, weight_str) = entry.split_once('=')
+            .ok_or_else(|| ParseError::InvalidBias(entry.to_string()))?;
+        let direction = dir_str.parse::<Direction>()
+            .map_err(|_| ParseError::InvalidBias(entry.to_string()))?;
+        weights[direction as usize] = weight_str.parse()
+            .map_err(|_| ParseError::InvalidBias(entry.to_string()))?;
+    }
+    
+    Ok(weights)
+}
+
+/// Parse `ant0=ColonyA,ant3=ColonyB` (the `ant` prefix is optional) into placements
+fn parse_placements(spec: &str) -> Result<Vec<(AntId, String)>, ParseError> {
+    spec.split(',')
+        .map(|entry| {
+            let (ant_str, colony_name) = entry.split_once('=')
+                .filter(|(_, name)| !name.is_empty())
+                .ok_or_else(|| ParseError::InvalidPlacement(entry.to_string()))?;
+            let ant_id = ant_str.strip_prefix("ant").unwrap_or(ant_str)
+                .parse()
+                .map_err(|_| ParseError::InvalidPlacement(entry.to_string()))?;
+            Ok((ant_id, colony_name.to_string()))
+        })
+        .collect()
 }
\ No newline at end of file
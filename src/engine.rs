@@ -1,51 +1,321 @@
-use crate::types::{ColonyId, AntId};
+use core::convert::Infallible;
+use core::ops::ControlFlow;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::time::Instant;
+use crate::types::{ColonyId, AntId, Direction, ColonySort, CollisionRule, DestroyedTargetPolicy};
 use crate::simulation::Simulation;
+#[cfg(feature = "std")]
+use crate::output;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 
-const MAX_ITERATIONS: u32 = 1_000_000;
+/// Iterations between wall-clock checks when a `time_limit` is set
+#[cfg(feature = "std")]
+const TIME_CHECK_INTERVAL: u32 = 1024;
 
 /// Main simulation engine with optimized hot path
 impl Simulation {
     /// Run the complete simulation until termination condition
+    ///
+    /// Determinism: with an explicit seed, the same map, ant count and config always produce an
+    /// identical `SimulationResult` (including every destruction message) on any platform.
+    /// Ants are placed and processed in ascending id order, all randomness comes from the seeded
+    /// `FastRng`, and a collision always reports the lowest-id ant occupying the target colony.
     pub fn run_simulation(&mut self) -> SimulationResult {
-        let mut iteration = 0;
-        let mut total_moves = 0;
         let mut destructions = Vec::new();
+        let summary = self.run_simulation_into(&mut destructions);
+        summary.with_destructions(destructions)
+    }
+    
+    /// Run to termination like `run_simulation`, appending destruction events to a caller-owned buffer
+    /// The buffer is not cleared first, so repeated runs can `clear()` and reuse one allocation
+    pub fn run_simulation_into(&mut self, destructions: &mut Vec<DestructionEvent>) -> SimulationSummary {
+        self.warm_up(self.config.warmup);
+        match self.run_loop(destructions, |_, _| Ok::<_, Infallible>(ControlFlow::Continue(()))) {
+            Ok(summary) => summary,
+            Err(never) => match never {},
+        }
+    }
+    
+    /// Run to termination like `run_simulation`, writing a replay log of every move and death
+    /// to `out` (format described in `output::write_replay_header`); ants must already be placed
+    #[cfg(feature = "std")]
+    pub fn run_simulation_recorded<W: Write>(&mut self, out: &mut W) -> io::Result<SimulationResult> {
+        self.warm_up(self.config.warmup);
+        output::write_replay_header(self, out)?;
         
-        loop {
-            let moves_this_iteration = self.step_simulation(&mut destructions);
-            total_moves += moves_this_iteration;
-            iteration += 1;
-            
-            // Check termination conditions
-            if self.all_ants_dead() || self.all_ants_reached_max_moves() {
-                break;
-            }
-            
-            // Safety check to prevent infinite loops
-            if iteration > MAX_ITERATIONS {
-                eprintln!("Warning: Simulation exceeded {} iterations, terminating", MAX_ITERATIONS);
-                break;
+        self.recording = true;
+        let mut destructions = Vec::new();
+        let summary = self.run_loop(&mut destructions, |sim, _| {
+            output::write_replay_step(sim, out)?;
+            Ok::<_, io::Error>(ControlFlow::Continue(()))
+        });
+        self.recording = false;
+        self.replay_events.clear();
+        
+        Ok(summary?.with_destructions(destructions))
+    }
+    
+    /// Run to termination like `run_simulation`, writing each destruction to `out` as a line of
+    /// JSON (see `output::write_destruction_json`) at the end of the iteration it happened in
+    /// `out` is flushed after every iteration that produced events, so a pipe or socket reader sees
+    /// them live; the returned result still holds every event
+    #[cfg(feature = "std")]
+    pub fn run_simulation_streaming<W: Write>(&mut self, out: &mut W) -> io::Result<SimulationResult> {
+        self.warm_up(self.config.warmup);
+        let mut destructions = Vec::new();
+        let mut written = 0;
+        let summary = self.run_loop(&mut destructions, |_, destructions| {
+            if destructions.len() > written {
+                for event in &destructions[written..] {
+                    output::write_destruction_json(event, out)?;
+                }
+                written = destructions.len();
+                out.flush()?;
             }
+            Ok::<_, io::Error>(ControlFlow::Continue(()))
+        })?;
+        Ok(summary.with_destructions(destructions))
+    }
+    
+    /// Run like `run_simulation` until `pred` holds, ending with `TerminationReason::StopCondition`
+    /// (e.g. "fewer than 10 colonies remain"); a normal termination or configured limit still ends
+    /// the run first. The predicate is checked once per iteration, after the whole step.
+    pub fn run_until(&mut self, mut pred: impl FnMut(&Simulation) -> bool) -> SimulationResult {
+        self.warm_up(self.config.warmup);
+        let mut destructions = Vec::new();
+        let stop_when = |sim: &Self, _: &[DestructionEvent]| {
+            Ok::<_, Infallible>(if pred(sim) { ControlFlow::Break(()) } else { ControlFlow::Continue(()) })
+        };
+        match self.run_loop(&mut destructions, stop_when) {
+            Ok(summary) => summary.with_destructions(destructions),
+            Err(never) => match never {},
         }
+    }
+    
+    /// Shared run loop; `after_step` sees the simulation and all destruction events so far after
+    /// every step, and can abort the run with an error or end it with `ControlFlow::Break`
+    fn run_loop<E>(&mut self, destructions: &mut Vec<DestructionEvent>, mut after_step: impl FnMut(&Self, &[DestructionEvent]) -> Result<ControlFlow<()>, E>) -> Result<SimulationSummary, E> {
+        let mut iteration = 0;
+        let mut total_moves: u64 = 0;
+        // Only read the clock when limited (`Instant` is unavailable on some targets, e.g. wasm)
+        #[cfg(feature = "std")]
+        let start_time = self.config.time_limit.map(|_| Instant::now());
+        // The buffer may hold earlier runs' events, so only count this run's
+        let first_destruction = destructions.len();
+        destructions.append(&mut self.spawn_destructions);
         
-        SimulationResult {
+        // A run resumed or warmed up past its end takes no further steps
+        let termination = match self.termination_reason().filter(|_| self.iteration > 0) {
+            Some(reason) => reason,
+            None => loop {
+                let moves_this_iteration = self.step_simulation(destructions);
+                total_moves += u64::from(moves_this_iteration);
+                iteration += 1;
+                let flow = after_step(self, destructions)?;
+                
+                // Check termination conditions
+                if let Some(reason) = self.termination_reason() {
+                    break reason;
+                }
+                
+                if flow.is_break() {
+                    break TerminationReason::StopCondition;
+                }
+                
+                if let Some(max_collisions) = self.config.max_collisions {
+                    if destructions.len() - first_destruction >= max_collisions as usize {
+                        break TerminationReason::CollisionLimit;
+                    }
+                }
+                
+                if self.config.max_total_moves.is_some_and(|limit| total_moves >= limit) {
+                    break TerminationReason::TotalMoveLimit;
+                }
+                
+                // Safety check to prevent infinite loops
+                if iteration >= self.config.max_iterations {
+                    break TerminationReason::IterationLimitExceeded;
+                }
+                
+                // Reading the clock every iteration would show up in short steps, so sample it
+                #[cfg(feature = "std")]
+                if let (Some(time_limit), Some(start_time)) = (self.config.time_limit, start_time) {
+                    if iteration.is_multiple_of(TIME_CHECK_INTERVAL) && start_time.elapsed() >= time_limit {
+                        break TerminationReason::TimeLimit;
+                    }
+                }
+            },
+        };
+        
+        Ok(SimulationSummary {
             iterations: iteration,
+            termination,
             total_moves,
-            destructions,
+            captures: core::mem::take(&mut self.captures),
+            oscillations: self.oscillations.clone(),
+            total_collisions: self.total_collisions,
             surviving_colonies: self.get_surviving_colonies(),
+            colony_visits: self.get_colony_visits(),
+            death_causes: if self.config.death_causes { self.ant_death_cause.clone() } else { Vec::new() },
+            move_distribution: if self.config.move_stats { self.move_stats() } else { MoveStats::default() },
+        })
+    }
+    
+    /// Advance the simulation by a single iteration, appending any destruction events
+    /// Returns the number of ant moves made; combined with `is_finished` this lets callers
+    /// drive a run step by step (e.g. to checkpoint it part-way through)
+    pub fn step(&mut self, destructions: &mut Vec<DestructionEvent>) -> u32 {
+        self.step_simulation(destructions)
+    }
+    
+    /// Let every colony that starts with more occupying ants than its capacity fight at once: the colony is
+    /// destroyed with all its ants, and a destruction event for iteration 0 naming its two
+    /// lowest-id occupants goes to `spawn_destructions`. Ants are grouped by colony in one
+    /// sort, so a crowd of ants on a small map is resolved in a single pass.
+    pub(crate) fn resolve_spawn_collisions(&mut self) {
+        let mut crowded: Vec<(ColonyId, AntId)> = (0..self.ant_colonies.len())
+            .filter(|&ant_idx| {
+                let colony_idx = self.ant_colonies[ant_idx] as usize;
+                self.ant_alive[ant_idx] && self.colony_ant_count[colony_idx] > self.colony_capacity[colony_idx] as AntId
+            })
+            .map(|ant_idx| (self.ant_colonies[ant_idx], ant_idx as AntId))
+            .collect();
+        // Stable, so each colony's ants stay in id order
+        crowded.sort_by_key(|&(colony_id, _)| colony_id);
+        
+        for group in crowded.chunk_by(|a, b| a.0 == b.0) {
+            let colony_id = group[0].0;
+            // Settled ants that don't block are in the colony but take no part in the fight
+            let mut fighters = group.iter()
+                .map(|&(_, ant_id)| ant_id)
+                .filter(|&ant_id| self.config.settled_ants_block || self.ant_max_moves[ant_id as usize] > 0);
+            let (Some(ant_a), Some(ant_b)) = (fighters.next(), fighters.next()) else {
+                continue;
+            };
+            
+            self.spawn_destructions.push(DestructionEvent {
+                iteration: 0,
+                colony: colony_id,
+                colony_name: self.colony_names[colony_id as usize].to_string(),
+                ant_a,
+                ant_b,
+            });
+            self.total_collisions += 1;
+            if let Some(&(_, traced)) = group.iter().find(|&&(_, ant_id)| self.is_traced(ant_id)) {
+                self.trace(format!("COLLISION at spawn in {}: ant {traced} DIED and the colony was destroyed",
+                                   &self.colony_names[colony_id as usize]));
+            }
+            
+            // Occupancy is cleared wholesale by `destroy_colony`, avoiding a rescan per removed ant
+            for &(_, ant_id) in group {
+                let blocks = self.config.settled_ants_block || self.ant_max_moves[ant_id as usize] > 0;
+                let cause = if blocks { DeathCause::Collision(colony_id) } else { DeathCause::ColonyDestroyedUnderneath(colony_id) };
+                self.mark_dead(ant_id, cause);
+            }
+            self.destroy_colony(colony_id);
+        }
+    }
+    
+    /// Step up to `iterations` times (fewer if the run finishes), then discard the destruction events
+    /// and reset the collision, capture, oscillation, heatmap and snapshot statistics
+    /// Ants keep their positions, move counts and death causes, and destroyed colonies stay destroyed,
+    /// so the surviving colonies of a later run include warmup destructions
+    /// The `run_simulation*` functions call this with `config.warmup`; returns the steps taken
+    pub fn warm_up(&mut self, iterations: u32) -> u32 {
+        let mut discarded = Vec::new();
+        let mut steps = 0;
+        while steps < iterations && !self.is_finished() {
+            self.step_simulation(&mut discarded);
+            discarded.clear();
+            steps += 1;
+        }
+        
+        if steps > 0 {
+            self.spawn_destructions.clear();
+            self.total_collisions = 0;
+            self.captures.clear();
+            self.oscillations.clear();
+            self.colony_visits.fill(0);
+            self.occupancy_snapshots.clear();
+        }
+        steps
+    }
+    
+    /// Distribution of the moves made by every ant so far, dead or alive
+    pub fn move_stats(&self) -> MoveStats {
+        let Some(&max) = self.ant_moves.iter().max() else {
+            return MoveStats::default();
+        };
+        let min = self.ant_moves.iter().copied().min().unwrap_or(0);
+        let total: u64 = self.ant_moves.iter().map(|&moves| moves as u64).sum();
+        
+        let bucket_width = max / MOVE_HISTOGRAM_BUCKETS as u32 + 1;
+        let mut histogram = [0; MOVE_HISTOGRAM_BUCKETS];
+        for &moves in &self.ant_moves {
+            histogram[(moves / bucket_width) as usize] += 1;
+        }
+        
+        MoveStats {
+            ants: self.ant_moves.len(),
+            min,
+            max,
+            mean: total as f64 / self.ant_moves.len() as f64,
+            bucket_width,
+            histogram,
+        }
+    }
+    
+    /// Check whether a normal termination condition has been reached
+    pub fn is_finished(&self) -> bool {
+        self.termination_reason().is_some()
+    }
+    
+    /// The normal termination condition currently met, if any
+    /// A lone survivor can never collide again, so the final map is already settled
+    pub fn termination_reason(&self) -> Option<TerminationReason> {
+        if self.all_ants_dead() {
+            Some(TerminationReason::AllAntsDead)
+        } else if self.all_ants_reached_max_moves() {
+            Some(TerminationReason::AllAntsReachedMaxMoves)
+        } else if self.living_count == 1 {
+            Some(TerminationReason::OnlyOneAntLeft)
+        } else {
+            None
         }
     }
     
     /// Execute one step of the simulation (process all living ants once)
     /// Uses two-phase approach: calculate moves, then apply them with collision detection
     #[inline(always)]
-    fn step_simulation(&mut self, destructions: &mut Vec<String>) -> u32 {
+    fn step_simulation(&mut self, destructions: &mut Vec<DestructionEvent>) -> u32 {
+        self.iteration += 1;
         let mut moves_count = 0;
+        if self.recording {
+            self.replay_events.clear();
+        }
         let mut pending_moves = Vec::with_capacity(self.ant_colonies.len() / 2); // Pre-allocate capacity
+        // Only read the clock when profiling (`Instant` is unavailable on some targets, e.g. wasm)
+        #[cfg(feature = "std")]
+        let phase_start = self.config.profile.then(Instant::now);
+        
+        // With `shuffle_order`, both phases visit ants in a fresh permutation (Phase 2 applies
+        // moves in the order Phase 1 queued them), so low ids no longer always move first
+        let mut ant_order = core::mem::take(&mut self.ant_order);
+        if self.config.shuffle_order {
+            ant_order.clear();
+            ant_order.extend((0..self.ant_colonies.len()).map(|ant_id| ant_id as AntId));
+            self.rng.shuffle(&mut ant_order);
+        } else {
+            ant_order.clear();
+        }
         
         // Phase 1: Calculate moves for all living ants (based on current state)
-        for ant_id in 0..self.ant_colonies.len() {
-            let ant_id = ant_id as AntId;
+        for index in 0..self.ant_colonies.len() {
+            let ant_id = ant_order.get(index).copied().unwrap_or(index as AntId);
             
             // Skip dead ants efficiently (branch prediction optimization)
             if !self.ant_alive[ant_id as usize] {
@@ -53,7 +323,7 @@ impl Simulation {
             }
             
             // Check if ant has reached move limit
-            if self.ant_moves[ant_id as usize] >= self.config.max_moves {
+            if self.ant_moves[ant_id as usize] >= self.ant_max_moves[ant_id as usize] {
                 continue;
             }
             
@@ -62,10 +332,19 @@ impl Simulation {
                 pending_moves.push((ant_id, target_colony));
             } else {
                 // Ant is trapped, just increment move counter
-                self.ant_moves[ant_id as usize] += 1;
+                self.record_move(ant_id);
+                self.vacate_if_settled(ant_id);
                 moves_count += 1;
             }
         }
+        self.ant_order = ant_order;
+        
+        #[cfg(feature = "std")]
+        let phase_start = phase_start.map(|start| {
+            let now = Instant::now();
+            self.phase_times.calculate += now - start;
+            now
+        });
         
         // Phase 2: Apply moves sequentially with collision detection
         for (ant_id, target_colony) in pending_moves {
@@ -76,33 +355,132 @@ impl Simulation {
             }
         }
         
+        #[cfg(feature = "std")]
+        if let Some(start) = phase_start {
+            self.phase_times.apply += start.elapsed();
+            self.phase_times.steps += 1;
+        }
+        
+        if self.config.snapshot_every.is_some_and(|every| self.iteration.is_multiple_of(every)) {
+            self.occupancy_snapshots.push((self.iteration, self.occupancy_snapshot()));
+        }
+        
+        #[cfg(feature = "invariants")]
+        self.check_invariants();
+        
         moves_count
     }
     
-    /// Calculate where an ant wants to move (Phase 1 - no state changes)
+    /// Panic with a description of the first inconsistency in the occupancy bookkeeping
+    /// Checks that every colony's ant count matches the living ants actually there, that destroyed
+    /// colonies hold no counted ants (survivors of a collision stay there, trapped, but uncounted),
+    /// and that each first ant is alive and occupying its colony. O(ants + colonies);
+    /// runs after every step with the `invariants` feature
+    pub fn check_invariants(&self) {
+        let mut actual_count = vec![0usize; self.num_colonies];
+        for (ant_idx, &colony_id) in self.ant_colonies.iter().enumerate() {
+            if !self.ant_alive[ant_idx] {
+                continue;
+            }
+            let colony_idx = colony_id as usize;
+            // Settled ants don't occupy their colony unless they block
+            if self.colony_valid[colony_idx] && self.blocks(ant_idx) {
+                actual_count[colony_idx] += 1;
+            }
+        }
+        
+        for (colony_idx, &actual) in actual_count.iter().enumerate() {
+            let name = &self.colony_names[colony_idx];
+            let recorded = self.colony_ant_count[colony_idx] as usize;
+            assert_eq!(recorded, actual,
+                       "iteration {}: colony {} records {} ants but {} living ants are there", self.iteration, name, recorded, actual);
+            
+            if !self.colony_valid[colony_idx] {
+                assert!(self.colony_first_ant[colony_idx].is_none(),
+                        "iteration {}: destroyed colony {} still has a first ant", self.iteration, name);
+            }
+            if let Some(first_ant) = self.colony_first_ant[colony_idx] {
+                let first_idx = first_ant as usize;
+                assert!(self.ant_alive[first_idx] && self.ant_colonies[first_idx] as usize == colony_idx && self.blocks(first_idx),
+                        "iteration {}: colony {} names ant {} as its first ant, but that ant doesn't occupy it", self.iteration, name, first_ant);
+            }
+        }
+    }
+    
+    /// Every destination the ant could pick on its next move, without sampling (the RNG is untouched)
+    /// Empty for a dead ant, one at its move limit, or one that is trapped or in a destroyed colony
+    /// Zero-weight directions are left out when another candidate has a positive weight
+    pub fn preview_valid_moves(&self, ant_id: AntId) -> Vec<(Direction, ColonyId)> {
+        let ant_idx = ant_id as usize;
+        if !self.is_ant_alive(ant_id) || self.ant_moves[ant_idx] >= self.ant_max_moves[ant_idx] {
+            return Vec::new();
+        }
+        
+        let mut valid_dirs = self.movable_directions(ant_id);
+        if self.weighted_directions {
+            let weights = &self.config.direction_weights;
+            let weighted_dirs = Direction::from_bit_mask(valid_dirs)
+                .filter(|&direction| weights[direction as usize] > 0)
+                .fold(0, |mask, direction| mask | direction.as_bit_mask());
+            if weighted_dirs != 0 {
+                valid_dirs = weighted_dirs;
+            }
+        }
+        
+        let current_colony = self.ant_colonies[ant_idx];
+        Direction::from_bit_mask(valid_dirs)
+            .filter_map(|direction| Some((direction, self.get_neighbor(current_colony, direction)?)))
+            .collect()
+    }
+    
+    /// Directions the ant may take from its colony (none if the colony was destroyed)
     #[inline(always)]
-    fn calculate_ant_move(&mut self, ant_id: AntId) -> Option<ColonyId> {
+    fn movable_directions(&self, ant_id: AntId) -> u8 {
         let ant_idx = ant_id as usize;
         let current_colony = self.ant_colonies[ant_idx];
         let current_colony_idx = current_colony as usize;
         
-        // Check if current colony is destroyed
+        // Ants left in a destroyed colony are trapped there
         if !self.colony_valid[current_colony_idx] {
-            // Ant will die, no move
-            return None;
+            return 0;
         }
         
         // Get valid directions for current colony
         let valid_dirs = self.colony_valid_dirs[current_colony_idx];
         
+        // Exclude the way back unless it's the only option
+        if self.config.no_backtrack {
+            if let Some(prev_colony) = self.ant_prev_colony[ant_idx] {
+                let forward_dirs = valid_dirs & !self.directions_towards(current_colony, prev_colony);
+                if forward_dirs != 0 {
+                    return forward_dirs;
+                }
+            }
+        }
+        valid_dirs
+    }
+    
+    /// Calculate where an ant wants to move (Phase 1 - no state changes)
+    #[inline(always)]
+    fn calculate_ant_move(&mut self, ant_id: AntId) -> Option<ColonyId> {
+        let valid_dirs = self.movable_directions(ant_id);
+        
         // Check if ant is trapped (no valid moves)
         if valid_dirs == 0 {
+            if self.is_traced(ant_id) {
+                self.trace_choice(ant_id, valid_dirs, None);
+            }
             // Ant stays in place (no move)
             return None;
         }
         
         // Select random direction
-        if let Some(direction) = self.select_random_direction(valid_dirs) {
+        let current_colony = self.ant_colonies[ant_id as usize];
+        let direction = self.select_random_direction(valid_dirs);
+        if self.is_traced(ant_id) {
+            self.trace_choice(ant_id, valid_dirs, direction);
+        }
+        if let Some(direction) = direction {
             if let Some(target_colony) = self.get_neighbor(current_colony, direction) {
                 // Check if target colony is valid
                 if self.colony_valid[target_colony as usize] {
@@ -117,16 +495,27 @@ impl Simulation {
     
     /// Move ant to target colony and handle collision detection
     #[inline(always)]
-    fn move_ant_to_colony(&mut self, ant_id: AntId, target_colony: ColonyId, destructions: &mut Vec<String>) {
+    fn move_ant_to_colony(&mut self, ant_id: AntId, target_colony: ColonyId, destructions: &mut Vec<DestructionEvent>) {
         let ant_idx = ant_id as usize;
         let current_colony = self.ant_colonies[ant_idx];
         let target_idx = target_colony as usize;
         
         // Check if target colony has been destroyed since move calculation
         if !self.colony_valid[target_idx] {
-            // Target colony destroyed, ant dies
-            self.remove_ant_from_colony(ant_id, current_colony);
-            self.kill_ant(ant_id);
+            match self.config.destroyed_target_policy {
+                DestroyedTargetPolicy::KillAnt => {
+                    self.remove_ant_from_colony(ant_id, current_colony);
+                    self.mark_dead(ant_id, DeathCause::EnteredDestroyedColony(target_colony));
+                }
+                DestroyedTargetPolicy::StayPut => {
+                    if self.is_traced(ant_id) {
+                        self.trace(format!("stayed put: {} was destroyed earlier this iteration", &self.colony_names[target_idx]));
+                    }
+                    // The wasted attempt still counts against the ant's move limit
+                    self.record_move(ant_id);
+                    self.vacate_if_settled(ant_id);
+                }
+            }
             return;
         }
         
@@ -134,41 +523,165 @@ impl Simulation {
         self.remove_ant_from_colony(ant_id, current_colony);
         
         // Check for collision AFTER removing from current but BEFORE adding to target
-        if self.colony_ant_count[target_idx] > 0 {
-            // Collision detected! Use O(1) tracking to find the other ant
-            let other_ant = self.colony_first_ant[target_idx];
-            
-            // Record destruction message
-            let colony_name = &self.colony_names[target_idx];
-            let destruction_msg = format!("{} has been destroyed by ant {} and ant {}!", 
-                                         colony_name, 
-                                         ant_id, 
-                                         other_ant.unwrap_or(0));
-            destructions.push(destruction_msg);
+        if self.colony_ant_count[target_idx] >= self.colony_capacity[target_idx] as AntId {
+            self.total_collisions += 1;
             
-            // Kill both ants
-            self.kill_ant(ant_id);
-            if let Some(other_ant) = other_ant {
-                self.kill_ant(other_ant);
+            if self.config.destroy_on_collision {
+                // Collision detected! Use O(1) tracking to find the other ant
+                // (the lowest-id occupant, so the reported pair never depends on placement order)
+                let other_ant = self.colony_first_ant[target_idx]
+                    .expect("occupied colony must track an occupant");
+                
+                match self.config.collision_rule {
+                    CollisionRule::DestroyColony => {
+                        self.destroy_in_collision(ant_id, other_ant, target_colony, destructions);
+                        return;
+                    }
+                    CollisionRule::LastWins => {
+                        // The occupant dies and the mover takes the colony below
+                        self.kill_ant(other_ant, DeathCause::LostCapture(target_colony));
+                        self.record_capture(target_colony, ant_id, other_ant);
+                    }
+                    CollisionRule::FirstWins => {
+                        // The mover dies on arrival (it has already left its colony)
+                        self.mark_dead(ant_id, DeathCause::LostCapture(target_colony));
+                        self.record_capture(target_colony, other_ant, ant_id);
+                        return;
+                    }
+                }
             }
-            
-            // Destroy colony
-            self.destroy_colony(target_colony);
+        }
+        
+        // No collision (or collisions don't destroy), move ant safely
+        if let Some(threshold) = self.config.oscillation_threshold {
+            self.track_oscillation(ant_id, current_colony, target_colony, threshold);
+        }
+        self.ant_prev_colony[ant_idx] = Some(current_colony);
+        self.record_move(ant_id);
+        if self.recording {
+            self.replay_events.push(ReplayEvent::Move { ant: ant_id, from: current_colony, to: target_colony });
+        }
+        
+        if self.is_traced(ant_id) {
+            self.trace(format!("moved to {}", &self.colony_names[target_idx]));
+        }
+        
+        // Place ant in target colony and update occupancy
+        self.occupy_colony(ant_id, target_colony);
+        self.vacate_if_settled(ant_id);
+        
+        if self.config.heatmap {
+            self.colony_visits[target_idx] += 1;
+        }
+    }
+    
+    /// Two ants fight: both die and the colony is destroyed
+    /// Any other ant still in the colony survives, trapped in the tombstoned colony
+    #[inline(always)]
+    fn destroy_in_collision(&mut self, ant_id: AntId, other_ant: AntId, target_colony: ColonyId, destructions: &mut Vec<DestructionEvent>) {
+        let target_idx = target_colony as usize;
+        
+        // Record destruction event (formatting is deferred to Display, off the hot path)
+        if self.is_traced(ant_id) || self.is_traced(other_ant) {
+            let (traced, partner) = if self.is_traced(ant_id) { (ant_id, other_ant) } else { (other_ant, ant_id) };
+            self.trace(format!("COLLISION with ant {partner} in {}: ant {traced} DIED and the colony was destroyed",
+                               &self.colony_names[target_idx]));
+        }
+        let (ant_a, ant_b) = if self.config.sort_collision_ants && other_ant < ant_id {
+            (other_ant, ant_id)
         } else {
-            // No collision, move ant safely
-            // Place ant in target colony
-            self.ant_colonies[ant_idx] = target_colony;
-            self.ant_moves[ant_idx] += 1;
-            
-            // Update target colony occupancy
-            self.colony_ant_count[target_idx] += 1;  // INCREMENT, don't set to 1!
-            self.colony_first_ant[target_idx] = Some(ant_id);
+            (ant_id, other_ant)
+        };
+        destructions.push(DestructionEvent {
+            iteration: self.iteration,
+            colony: target_colony,
+            colony_name: self.colony_names[target_idx].to_string(),
+            ant_a,
+            ant_b,
+        });
+        
+        // Kill both ants (the moving ant has already left its colony)
+        self.mark_dead(ant_id, DeathCause::Collision(target_colony));
+        self.kill_ant(other_ant, DeathCause::Collision(target_colony));
+        
+        // Destroy colony
+        self.destroy_colony(target_colony);
+    }
+    
+    /// Count consecutive moves spent bouncing between the same two colonies, reporting an ant
+    /// once when its streak reaches `threshold`
+    #[inline]
+    fn track_oscillation(&mut self, ant_id: AntId, current_colony: ColonyId, target_colony: ColonyId, threshold: u32) {
+        let ant_idx = ant_id as usize;
+        let streak = if self.ant_prev_colony[ant_idx] == Some(target_colony) {
+            self.ant_oscillation_streak[ant_idx] + 1
+        } else {
+            1
+        };
+        self.ant_oscillation_streak[ant_idx] = streak;
+        
+        if streak == threshold {
+            self.oscillations.push((ant_id, current_colony.min(target_colony), current_colony.max(target_colony)));
+        }
+    }
+    
+    /// Record a non-destructive collision under the `LastWins`/`FirstWins` rules
+    #[cold]
+    fn record_capture(&mut self, colony: ColonyId, winner: AntId, loser: AntId) {
+        if self.is_traced(winner) {
+            self.trace(format!("won the fight for {} against ant {loser}", &self.colony_names[colony as usize]));
         }
+        self.captures.push(CaptureEvent {
+            iteration: self.iteration,
+            colony,
+            colony_name: self.colony_names[colony as usize].to_string(),
+            winner,
+            loser,
+        });
+    }
+    
+    /// Whether `ant_id` is the ant followed by `config.trace_ant`
+    #[inline(always)]
+    fn is_traced(&self, ant_id: AntId) -> bool {
+        self.config.trace_ant == Some(ant_id)
+    }
+    
+    /// Add a line to `ant_trace`, prefixed with the current iteration
+    #[cold]
+    fn trace(&mut self, event: String) {
+        self.ant_trace.push(format!("iteration {}: {event}", self.iteration));
+    }
+    
+    /// Trace the traced ant's options and the direction it drew (`None` when trapped)
+    #[cold]
+    fn trace_choice(&mut self, ant_id: AntId, valid_dirs: u8, direction: Option<Direction>) {
+        let colony = &self.colony_names[self.ant_colonies[ant_id as usize] as usize];
+        let event = match direction {
+            Some(direction) => {
+                let options: Vec<&str> = Direction::from_bit_mask(valid_dirs).map(Direction::name).collect();
+                format!("at {colony}, valid directions {}, chose {}", options.join(","), direction.name())
+            }
+            None => format!("at {colony}, no valid directions: trapped"),
+        };
+        self.trace(event);
+    }
+    
+    /// Trace how the traced ant died (collisions are traced by the caller, which knows both ants)
+    #[cold]
+    fn trace_death(&mut self, cause: DeathCause) {
+        let colony = &self.colony_names[cause.colony() as usize];
+        let event = match cause {
+            DeathCause::Collision(_) => format!("DIED in a collision in {colony}"),
+            DeathCause::EnteredDestroyedColony(_) => format!("DIED entering {colony}, destroyed earlier this iteration"),
+            DeathCause::ColonyDestroyedUnderneath(_) => format!("DIED when {colony} was destroyed around it"),
+            DeathCause::LostCapture(_) => format!("DIED losing the fight for {colony}"),
+        };
+        self.trace(event);
     }
     
     /// Remove ant from colony (update occupancy tracking)
     #[inline(always)]
-    fn remove_ant_from_colony(&mut self, ant_id: AntId, colony_id: ColonyId) {
+    pub(crate) fn remove_ant_from_colony(&mut self, ant_id: AntId, colony_id: ColonyId) {
         let colony_idx = colony_id as usize;
         
         // Decrease occupancy count
@@ -178,23 +691,93 @@ impl Simulation {
         
         // Update first ant tracking
         if self.colony_first_ant[colony_idx] == Some(ant_id) {
-            self.colony_first_ant[colony_idx] = None;
+            self.colony_first_ant[colony_idx] = if self.colony_ant_count[colony_idx] > 0 {
+                self.lowest_other_occupant(colony_id, ant_id)
+            } else {
+                None
+            };
         }
     }
     
-    /// Kill an ant (mark as dead)
+    /// Find the lowest-id living ant in a colony other than `leaving_ant` that still occupies it
+    /// Only needed when ants spawned together, so the linear scan stays off the common path
+    #[cold]
+    fn lowest_other_occupant(&self, colony_id: ColonyId, leaving_ant: AntId) -> Option<AntId> {
+        self.ant_colonies
+            .iter()
+            .enumerate()
+            .find(|&(idx, &colony)| {
+                colony == colony_id && idx != leaving_ant as usize && self.ant_alive[idx] && self.blocks(idx)
+            })
+            .map(|(idx, _)| idx as AntId)
+    }
+    
+    /// Increment an ant's move counter, tracking when it reaches the move limit
+    /// Callers only move ants below their limit, so the u32 counter can never wrap
     #[inline(always)]
-    fn kill_ant(&mut self, ant_id: AntId) {
+    fn record_move(&mut self, ant_id: AntId) {
         let ant_idx = ant_id as usize;
-        if ant_idx < self.ant_alive.len() {
-            self.ant_alive[ant_idx] = false;
-            
+        self.ant_moves[ant_idx] += 1;
+        if self.ant_moves[ant_idx] == self.ant_max_moves[ant_idx] {
+            self.ants_at_max_moves += 1;
+        }
+    }
+    
+    /// Whether an ant occupies its colony: always with `settled_ants_block`, otherwise only
+    /// until it has used its last move
+    #[inline(always)]
+    fn blocks(&self, ant_idx: usize) -> bool {
+        self.config.settled_ants_block || self.ant_moves[ant_idx] < self.ant_max_moves[ant_idx]
+    }
+    
+    /// Without `settled_ants_block`, an ant that has just used its last move stops occupying
+    /// its colony, so later arrivals pass through instead of colliding with it
+    #[inline(always)]
+    fn vacate_if_settled(&mut self, ant_id: AntId) {
+        if !self.config.settled_ants_block && self.ant_moves[ant_id as usize] == self.ant_max_moves[ant_id as usize] {
+            let colony_id = self.ant_colonies[ant_id as usize];
+            self.remove_ant_from_colony(ant_id, colony_id);
+        }
+    }
+    
+    /// Kill an ant (mark as dead and remove it from its colony)
+    #[inline(always)]
+    fn kill_ant(&mut self, ant_id: AntId, cause: DeathCause) {
+        if self.mark_dead(ant_id, cause) {
             // Remove from current colony
-            let current_colony = self.ant_colonies[ant_idx];
+            let current_colony = self.ant_colonies[ant_id as usize];
             self.remove_ant_from_colony(ant_id, current_colony);
         }
     }
     
+    /// Mark an ant as dead without touching colony occupancy (for ants that already left)
+    /// Returns false if the ant was already dead
+    #[inline(always)]
+    fn mark_dead(&mut self, ant_id: AntId, cause: DeathCause) -> bool {
+        let ant_idx = ant_id as usize;
+        // Guard against double-kill so the live counters are only decremented once
+        if ant_idx >= self.ant_alive.len() || !self.ant_alive[ant_idx] {
+            return false;
+        }
+        
+        self.ant_alive.set(ant_idx, false);
+        // Collisions are traced where both ants are known
+        if self.is_traced(ant_id) && !matches!(cause, DeathCause::Collision(_)) {
+            self.trace_death(cause);
+        }
+        if self.config.death_causes {
+            self.ant_death_cause[ant_idx] = Some(cause);
+        }
+        self.living_count -= 1;
+        if self.ant_moves[ant_idx] >= self.ant_max_moves[ant_idx] {
+            self.ants_at_max_moves -= 1;
+        }
+        if self.recording {
+            self.replay_events.push(ReplayEvent::Death { ant: ant_id });
+        }
+        true
+    }
+    
     /// Destroy a colony using tombstoning (O(1) operation)
     fn destroy_colony(&mut self, colony_id: ColonyId) {
         let colony_idx = colony_id as usize;
@@ -203,7 +786,8 @@ impl Simulation {
         }
         
         // Mark colony as invalid (tombstoning)
-        self.colony_valid[colony_idx] = false;
+        self.colony_valid.set(colony_idx, false);
+        self.colony_destroyed_at[colony_idx] = Some(self.iteration);
         
         // Clear occupancy
         self.colony_ant_count[colony_idx] = 0;
@@ -214,123 +798,318 @@ impl Simulation {
     }
     
     /// Update neighbor colonies' valid directions after colony destruction
+    /// Walks the direction array directly: `neighbors` borrows `self`, and collecting it would allocate per destruction
     fn update_neighbors_after_destruction(&mut self, destroyed_colony: ColonyId) {
-        let destroyed_idx = destroyed_colony as usize;
-        
-        // Check all four directions for neighbors
-        let neighbors = [
-            self.colony_north[destroyed_idx],
-            self.colony_south[destroyed_idx],
-            self.colony_east[destroyed_idx],
-            self.colony_west[destroyed_idx],
-        ];
-        
-        for neighbor_id in neighbors.iter().flatten() {
-            self.update_valid_directions(*neighbor_id);
+        for direction in Direction::ALL {
+            if let Some(neighbor_id) = self.tunnel(destroyed_colony as usize, direction) {
+                self.update_valid_directions(neighbor_id);
+            }
         }
     }
     
-    /// Check if all ants are dead (early termination optimization)
+    /// Check if all ants are dead (O(1) via the live counter)
     #[inline]
     fn all_ants_dead(&self) -> bool {
-        // Use iterator any for early exit
-        !self.ant_alive.iter().any(|&alive| alive)
+        self.living_count == 0
     }
     
-    /// Check if all living ants have reached max moves (early termination optimization)
+    /// Check if all living ants have reached max moves (O(1) via the live counters)
     #[inline]
     fn all_ants_reached_max_moves(&self) -> bool {
-        // Use iterator with zip for better performance
-        self.ant_alive
-            .iter()
-            .zip(self.ant_moves.iter())
-            .all(|(&alive, &moves)| !alive || moves >= self.config.max_moves)
+        self.ants_at_max_moves == self.living_count
     }
     
     
-    /// Get list of surviving colonies in the same format as input
+    /// Get per-colony visit counts sorted by count descending (empty unless `heatmap` is enabled)
+    fn get_colony_visits(&self) -> Vec<(String, u32)> {
+        if !self.config.heatmap {
+            return Vec::new();
+        }
+        
+        let mut visits: Vec<(String, u32)> = self.colony_names
+            .iter()
+            .map(str::to_string)
+            .zip(self.colony_visits.iter().copied())
+            .collect();
+        
+        // Ties broken by name for stable output
+        visits.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        visits
+    }
+    
+    /// Get list of surviving colonies in the same format as input (also what `Display` prints)
     fn get_surviving_colonies(&self) -> Vec<String> {
+        self.map_lines(self.config.sort_colonies)
+    }
+    
+    /// Render every valid colony and its tunnels to other valid colonies in the map input format
+    /// On a freshly parsed map this is the canonical form of the whole input
+    pub fn map_lines(&self, sort: ColonySort) -> Vec<String> {
         let mut survivors = Vec::new();
         
-        for (i, &valid) in self.colony_valid.iter().enumerate() {
+        for (i, valid) in self.colony_valid.iter().enumerate() {
             if valid {
                 let colony_name = &self.colony_names[i];
 
-                let mut connection_count = 0;
-                // Pre-calculate required capacity to avoid reallocations
-                let mut estimated_length = colony_name.len();
+                let colony_id = i as ColonyId;
                 
-                // Count valid connections and estimate length
-                if let Some(north_id) = self.colony_north[i] {
-                    if self.colony_valid[north_id as usize] {
-                        estimated_length += 7 + self.colony_names[north_id as usize].len(); // " north="
-                        connection_count += 1;
-                    }
-                }
-                if let Some(south_id) = self.colony_south[i] {
-                    if self.colony_valid[south_id as usize] {
-                        estimated_length += 7 + self.colony_names[south_id as usize].len(); // " south="
-                        connection_count += 1;
-                    }
-                }
-                if let Some(east_id) = self.colony_east[i] {
-                    if self.colony_valid[east_id as usize] {
-                        estimated_length += 6 + self.colony_names[east_id as usize].len(); // " east="
-                        connection_count += 1;
-                    }
-                }
-                if let Some(west_id) = self.colony_west[i] {
-                    if self.colony_valid[west_id as usize] {
-                        estimated_length += 6 + self.colony_names[west_id as usize].len(); // " west="
-                        connection_count += 1;
-                    }
-                }
+                // Pre-calculate required capacity to avoid reallocations (" direction=" plus the name)
+                let estimated_length = colony_name.len() + self.valid_neighbors(colony_id)
+                    .map(|(direction, neighbor)| 2 + direction.name().len() + self.colony_names[neighbor as usize].len())
+                    .sum::<usize>();
                 
                 // Build output string with pre-allocated capacity
                 let mut output_line = String::with_capacity(estimated_length);
-                output_line.push_str(colony_name);
+                push_map_name(&mut output_line, colony_name);
+                if self.colony_capacity[i] != 1 {
+                    output_line.push(':');
+                    output_line.push_str(&self.colony_capacity[i].to_string());
+                }
                 
                 // Add connections directly without intermediate Vec
-                if let Some(north_id) = self.colony_north[i] {
-                    if self.colony_valid[north_id as usize] {
-                        output_line.push_str(" north=");
-                        output_line.push_str(&self.colony_names[north_id as usize]);
-                    }
-                }
-                if let Some(south_id) = self.colony_south[i] {
-                    if self.colony_valid[south_id as usize] {
-                        output_line.push_str(" south=");
-                        output_line.push_str(&self.colony_names[south_id as usize]);
-                    }
-                }
-                if let Some(east_id) = self.colony_east[i] {
-                    if self.colony_valid[east_id as usize] {
-                        output_line.push_str(" east=");
-                        output_line.push_str(&self.colony_names[east_id as usize]);
-                    }
-                }
-                if let Some(west_id) = self.colony_west[i] {
-                    if self.colony_valid[west_id as usize] {
-                        output_line.push_str(" west=");
-                        output_line.push_str(&self.colony_names[west_id as usize]);
+                for (direction, neighbor) in self.valid_neighbors(colony_id) {
+                    output_line.push(' ');
+                    output_line.push_str(direction.name());
+                    if self.is_one_way(colony_id, direction) {
+                        output_line.push('>');
                     }
+                    output_line.push('=');
+                    push_map_name(&mut output_line, &self.colony_names[neighbor as usize]);
                 }
                 
-                survivors.push(output_line);
+                survivors.push((i, output_line));
             }
         }
         
-        // Sort for consistent output
-        survivors.sort();
-        survivors
+        // Sort for consistent output (ties fall back to the full line)
+        match sort {
+            ColonySort::Line => survivors.sort_by(|a, b| a.1.cmp(&b.1)),
+            ColonySort::Name => survivors.sort_by(|a, b| self.colony_names[a.0].cmp(&self.colony_names[b.0])),
+            ColonySort::Degree => survivors.sort_by(|a, b| {
+                let degree = |idx: usize| self.colony_valid_dirs[idx].count_ones();
+                degree(b.0).cmp(&degree(a.0)).then_with(|| a.1.cmp(&b.1))
+            }),
+        }
+        survivors.into_iter().map(|(_, line)| line).collect()
+    }
+}
+
+/// The current map: every valid colony with its surviving tunnels, one per line, exactly as
+/// `SimulationResult::surviving_colonies` reports them at the end of a run
+impl core::fmt::Display for Simulation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for line in self.get_surviving_colonies() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Append a colony name as the parser reads it, quoting names containing `=` or whitespace
+fn push_map_name(output: &mut String, name: &str) {
+    if needs_quotes(name) {
+        output.push('"');
+        output.push_str(name);
+        output.push('"');
+    } else {
+        output.push_str(name);
+    }
+}
+
+/// Whether a name must be quoted to read back as a single name
+pub(crate) fn needs_quotes(name: &str) -> bool {
+    name.is_empty() || name.starts_with(['"', '#', '%']) || name.contains(|c: char| c == '=' || is_separator(c))
+        || name.rsplit_once(':').is_some_and(|(_, digits)| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Characters separating map tokens (see `parser`): Unicode whitespace (which covers the `\r` of CRLF line ends and
+/// non-breaking spaces) plus the invisible byte-order mark and zero-width space that editors and
+/// web pages leave behind
+pub(crate) fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}' || c == '\u{200b}'
+}
+
+/// One change of ant state in a recorded step (see `run_simulation_recorded`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayEvent {
+    Move { ant: AntId, from: ColonyId, to: ColonyId },
+    Death { ant: AntId },
+}
+
+/// A colony destroyed by two ants fighting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DestructionEvent {
+    pub iteration: u32,                             // 1-based iteration in which the fight happened
+    pub colony: ColonyId,
+    pub colony_name: String,
+    pub ant_a: AntId,                               // The ant that moved in (the lower id with `sort_collision_ants`)
+    pub ant_b: AntId,                               // The ant already there (the higher id with `sort_collision_ants`)
+}
+
+impl core::fmt::Display for DestructionEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} has been destroyed by ant {} and ant {}!", self.colony_name, self.ant_a, self.ant_b)
+    }
+}
+
+/// A colony taken over by one ant after a non-destructive fight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureEvent {
+    pub iteration: u32,
+    pub colony: ColonyId,
+    pub colony_name: String,
+    pub winner: AntId,                              // The ant left holding the colony
+    pub loser: AntId,                               // The ant that died
+}
+
+impl core::fmt::Display for CaptureEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} has been captured by ant {} (ant {} died)", self.colony_name, self.winner, self.loser)
+    }
+}
+
+/// How an ant died (recorded only with `death_causes` enabled)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Collision(ColonyId),                            // Fought another ant, destroying the colony
+    EnteredDestroyedColony(ColonyId),               // Moved into a colony destroyed earlier in the same iteration
+    ColonyDestroyedUnderneath(ColonyId),            // Took no part in a spawn collision that destroyed its colony
+    LostCapture(ColonyId),                          // Lost a fight under the `LastWins`/`FirstWins` rules
+}
+
+impl DeathCause {
+    pub fn colony(self) -> ColonyId {
+        match self {
+            DeathCause::Collision(colony)
+            | DeathCause::EnteredDestroyedColony(colony)
+            | DeathCause::ColonyDestroyedUnderneath(colony)
+            | DeathCause::LostCapture(colony) => colony,
+        }
     }
 }
 
+/// Why `run_simulation` stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminationReason {
+    AllAntsDead,
+    AllAntsReachedMaxMoves,
+    OnlyOneAntLeft,
+    IterationLimitExceeded,                         // Clipped by `max_iterations` rather than ending naturally
+    TimeLimit,                                      // Clipped by the wall-clock `time_limit`
+    CollisionLimit,                                 // Stopped early after `max_collisions` destructions
+    TotalMoveLimit,                                 // Stopped early after `max_total_moves` ant moves
+    StopCondition,                                  // Stopped by the predicate passed to `run_until`
+}
+
+impl core::fmt::Display for TerminationReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TerminationReason::AllAntsDead => write!(f, "all ants were destroyed"),
+            TerminationReason::AllAntsReachedMaxMoves => write!(f, "every surviving ant reached the move limit"),
+            TerminationReason::OnlyOneAntLeft => write!(f, "only one ant was left alive"),
+            TerminationReason::IterationLimitExceeded => write!(f, "iteration limit exceeded"),
+            TerminationReason::TimeLimit => write!(f, "time limit exceeded"),
+            TerminationReason::CollisionLimit => write!(f, "collision limit reached"),
+            TerminationReason::TotalMoveLimit => write!(f, "total move limit reached"),
+            TerminationReason::StopCondition => write!(f, "stop condition met"),
+        }
+    }
+}
+
+/// Time spent in the two phases of `step_simulation`, summed over `steps` steps (with `profile`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimes {
+    pub calculate: Duration,                        // Phase 1: choosing every ant's move
+    pub apply: Duration,                            // Phase 2: moving ants and resolving collisions
+    pub steps: u32,
+}
+
+/// Buckets in `MoveStats::histogram`
+pub const MOVE_HISTOGRAM_BUCKETS: usize = 10;
+
+/// How many moves the ants made: collision-dominated runs pile up at low counts,
+/// move-limit-dominated runs at `max_moves`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveStats {
+    pub ants: usize,
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+    pub bucket_width: u32,                          // Bucket `i` counts ants with `i * bucket_width..(i + 1) * bucket_width` moves
+    pub histogram: [usize; MOVE_HISTOGRAM_BUCKETS],
+}
+
 /// Result of a complete simulation run
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[must_use]
 pub struct SimulationResult {
     pub iterations: u32,
-    pub total_moves: u32,
-    pub destructions: Vec<String>,
+    pub termination: TerminationReason,
+    pub total_moves: u64,                           // Ants times moves can pass `u32::MAX` now that move limits are u32
+    pub destructions: Vec<DestructionEvent>,
+    pub captures: Vec<CaptureEvent>,                // Only under the `LastWins`/`FirstWins` collision rules
+    pub oscillations: Vec<(AntId, ColonyId, ColonyId)>, // Ants stuck between two colonies (with `oscillation_threshold`)
+    pub total_collisions: u64,                      // Includes collisions that didn't destroy (see `destroy_on_collision`)
+    pub surviving_colonies: Vec<String>,
+    pub colony_visits: Vec<(String, u32)>,          // Empty unless the heatmap is enabled
+    pub death_causes: Vec<Option<DeathCause>>,      // Indexed by ant id; empty unless `death_causes` is enabled
+    pub move_distribution: MoveStats,               // All zero unless `move_stats` is enabled
+}
+
+impl SimulationResult {
+    pub fn destruction_count(&self) -> usize {
+        self.destructions.len()
+    }
+    
+    pub fn survivor_count(&self) -> usize {
+        self.surviving_colonies.len()
+    }
+    
+    /// Whether no colony survived
+    pub fn was_fully_destroyed(&self) -> bool {
+        self.surviving_colonies.is_empty()
+    }
+    
+    /// Destructions per ant move (0 when no ant moved)
+    pub fn collision_rate(&self) -> f64 {
+        if self.total_moves == 0 {
+            0.0
+        } else {
+            self.destructions.len() as f64 / self.total_moves as f64
+        }
+    }
+}
+
+/// Everything in a `SimulationResult` except the destruction events (see `run_simulation_into`)
+#[derive(Debug)]
+pub struct SimulationSummary {
+    pub iterations: u32,
+    pub termination: TerminationReason,
+    pub total_moves: u64,
+    pub captures: Vec<CaptureEvent>,
+    pub oscillations: Vec<(AntId, ColonyId, ColonyId)>,
+    pub total_collisions: u64,
     pub surviving_colonies: Vec<String>,
+    pub colony_visits: Vec<(String, u32)>,
+    pub death_causes: Vec<Option<DeathCause>>,
+    pub move_distribution: MoveStats,
+}
+
+impl SimulationSummary {
+    /// Attach the destruction events to form a full result
+    pub fn with_destructions(self, destructions: Vec<DestructionEvent>) -> SimulationResult {
+        SimulationResult {
+            iterations: self.iterations,
+            termination: self.termination,
+            total_moves: self.total_moves,
+            destructions,
+            captures: self.captures,
+            oscillations: self.oscillations,
+            total_collisions: self.total_collisions,
+            surviving_colonies: self.surviving_colonies,
+            colony_visits: self.colony_visits,
+            death_causes: self.death_causes,
+            move_distribution: self.move_distribution,
+        }
+    }
 }
\ No newline at end of file
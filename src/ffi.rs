@@ -0,0 +1,165 @@
+//! C ABI for embedding the simulation in a non-Rust host
+//!
+//! Build a linkable library with `cargo rustc --release --lib --features ffi --crate-type staticlib`
+//! (or `cdylib`) and declare:
+//!
+//! ```c
+//! typedef struct AntManiaHandle AntManiaHandle;
+//!
+//! AntManiaHandle *ant_mania_create(const char *map_path);         /* NULL on error */
+//! int32_t ant_mania_run(AntManiaHandle *h, uint32_t num_ants, uint64_t seed, uint32_t max_moves);
+//! uint32_t ant_mania_iterations(const AntManiaHandle *h);
+//! uint64_t ant_mania_total_moves(const AntManiaHandle *h);
+//! size_t ant_mania_destruction_count(const AntManiaHandle *h);
+//! size_t ant_mania_survivor_count(const AntManiaHandle *h);
+//! uint64_t ant_mania_collision_count(const AntManiaHandle *h);   /* includes non-destroying collisions */
+//! const char *ant_mania_destruction(const AntManiaHandle *h, size_t index); /* NULL if out of range */
+//! void ant_mania_free(AntManiaHandle *h);
+//! ```
+//!
+//! Result accessors return 0 before the first successful run. Strings returned by
+//! `ant_mania_destruction` stay valid until the next `ant_mania_run` or `ant_mania_free`.
+//! No Rust panic crosses the boundary: it is caught and reported as `ANT_MANIA_ERR_PANIC`.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use crate::engine::SimulationResult;
+use crate::simulation::Simulation;
+use crate::types::{AntId, SimulationConfig};
+
+pub const ANT_MANIA_OK: i32 = 0;
+pub const ANT_MANIA_ERR_NULL: i32 = -1;
+pub const ANT_MANIA_ERR_INVALID: i32 = -2;
+pub const ANT_MANIA_ERR_PANIC: i32 = -3;
+
+/// Opaque handle owning a parsed map and the most recent run's results
+pub struct AntManiaHandle {
+    map: Simulation,                                // Parsed, never-run map reused by every run
+    result: Option<SimulationResult>,
+    destruction_messages: Vec<CString>,             // Formatted once per run so pointers stay stable
+}
+
+/// Parse the map at `map_path`; returns NULL if the path is invalid or the map fails to parse
+///
+/// # Safety
+/// `map_path` must be NULL or a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_create(map_path: *const c_char) -> *mut AntManiaHandle {
+    if map_path.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Ok(path) = CStr::from_ptr(map_path).to_str() else {
+        return std::ptr::null_mut();
+    };
+    
+    let created = catch_unwind(|| {
+        let map = Simulation::from_map_file(SimulationConfig::builder().map_file(path).build(), path).ok()?;
+        Some(Box::new(AntManiaHandle { map, result: None, destruction_messages: Vec::new() }))
+    });
+    
+    match created {
+        Ok(Some(handle)) => Box::into_raw(handle),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Run a fresh simulation on the handle's map, replacing any previous results
+///
+/// # Safety
+/// `handle` must be NULL or a pointer returned by `ant_mania_create` that hasn't been freed
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_run(handle: *mut AntManiaHandle, num_ants: u32, seed: u64, max_moves: u32) -> i32 {
+    let Some(handle) = handle.as_mut() else {
+        return ANT_MANIA_ERR_NULL;
+    };
+    let Some(num_ants) = AntId::try_from(num_ants).ok() else {
+        return ANT_MANIA_ERR_INVALID;
+    };
+    
+    let outcome = catch_unwind(AssertUnwindSafe(|| {
+        let mut sim = handle.map.clone();
+        sim.config.num_ants = num_ants;
+        sim.config.max_moves = max_moves;
+        sim.reset_ants(seed);
+        sim.initialize_ants(num_ants).ok()?;
+        Some(sim.run_simulation())
+    }));
+    
+    handle.result = None;
+    handle.destruction_messages.clear();
+    match outcome {
+        Ok(Some(result)) => {
+            // Colony names can't contain NUL (the parser splits on whitespace), but never panic here
+            handle.destruction_messages = result.destructions.iter()
+                .map(|event| CString::new(event.to_string()).unwrap_or_default())
+                .collect();
+            handle.result = Some(result);
+            ANT_MANIA_OK
+        }
+        Ok(None) => ANT_MANIA_ERR_INVALID,
+        Err(_) => ANT_MANIA_ERR_PANIC,
+    }
+}
+
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_iterations(handle: *const AntManiaHandle) -> u32 {
+    result_of(handle).map_or(0, |result| result.iterations)
+}
+
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_total_moves(handle: *const AntManiaHandle) -> u64 {
+    result_of(handle).map_or(0, |result| result.total_moves)
+}
+
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_destruction_count(handle: *const AntManiaHandle) -> usize {
+    result_of(handle).map_or(0, |result| result.destruction_count())
+}
+
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_survivor_count(handle: *const AntManiaHandle) -> usize {
+    result_of(handle).map_or(0, |result| result.survivor_count())
+}
+
+/// Collisions in the last run, whether or not they destroyed a colony
+///
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_collision_count(handle: *const AntManiaHandle) -> u64 {
+    result_of(handle).map_or(0, |result| result.total_collisions)
+}
+
+/// Destruction message `index` of the last run, or NULL if out of range
+///
+/// # Safety
+/// `handle` must be NULL or a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_destruction(handle: *const AntManiaHandle, index: usize) -> *const c_char {
+    handle.as_ref()
+        .and_then(|handle| handle.destruction_messages.get(index))
+        .map_or(std::ptr::null(), |message| message.as_ptr())
+}
+
+/// Release a handle; NULL is ignored
+///
+/// # Safety
+/// `handle` must be NULL or a live handle, and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn ant_mania_free(handle: *mut AntManiaHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+unsafe fn result_of<'a>(handle: *const AntManiaHandle) -> Option<&'a SimulationResult> {
+    handle.as_ref()?.result.as_ref()
+}
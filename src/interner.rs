@@ -0,0 +1,124 @@
+use core::ops::Index;
+use crate::types::ColonyId;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// Colony names stored once, back to back, with an id → name table and a name → id hash index
+/// Replaces a `Vec<String>` plus `HashMap<String, ColonyId>`, which kept every name twice
+/// and cost two allocations per colony
+#[derive(Debug, Clone, Default)]
+pub struct NameInterner {
+    arena: String,                                  // Every name, concatenated
+    spans: Vec<(usize, usize)>,                     // (start, end) of each colony's name in `arena`
+    slots: Vec<Option<ColonyId>>,                   // Open-addressing index by name hash (power-of-two size)
+    indexed: usize,                                 // Occupied slots
+}
+
+impl NameInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Number of colony ids with a (possibly empty) name
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+    
+    /// Remove every name, keeping the allocations for reuse
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.spans.clear();
+        self.slots.clear();
+        self.indexed = 0;
+    }
+    
+    /// Resize to `num_colonies` ids (new ids get an empty, unindexed name) and size the index for them
+    pub fn resize(&mut self, num_colonies: usize) {
+        self.spans.resize(num_colonies, (0, 0));
+        self.grow_index(num_colonies);
+    }
+    
+    /// Give `colony_id` a name and index it for lookup
+    /// The id must be below `len()`
+    pub fn set(&mut self, colony_id: ColonyId, name: &str) {
+        let start = self.arena.len();
+        self.arena.push_str(name);
+        self.spans[colony_id as usize] = (start, self.arena.len());
+        
+        self.grow_index(self.indexed + 1);
+        let slot = self.find_slot(name);
+        if self.slots[slot].is_none() {
+            self.indexed += 1;
+        }
+        self.slots[slot] = Some(colony_id);
+    }
+    
+    /// Name of a colony id, or `None` if out of range
+    pub fn get(&self, colony_id: ColonyId) -> Option<&str> {
+        self.spans.get(colony_id as usize).map(|&(start, end)| &self.arena[start..end])
+    }
+    
+    /// Id of the colony with this name
+    pub fn id_of(&self, name: &str) -> Option<ColonyId> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        self.slots[self.find_slot(name)]
+    }
+    
+    pub fn contains(&self, name: &str) -> bool {
+        self.id_of(name).is_some()
+    }
+    
+    /// Names in id order
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.spans.iter().map(|&(start, end)| &self.arena[start..end])
+    }
+    
+    /// Slot holding `name`, or the empty slot where it would go (linear probing)
+    fn find_slot(&self, name: &str) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = hash(name) as usize & mask;
+        while let Some(id) = self.slots[slot] {
+            if self.get(id) == Some(name) {
+                break;
+            }
+            slot = (slot + 1) & mask;
+        }
+        slot
+    }
+    
+    /// Keep the index at most half full for `entries` names, rehashing when it grows
+    fn grow_index(&mut self, entries: usize) {
+        let wanted = (entries * 2).next_power_of_two().max(16);
+        if wanted <= self.slots.len() {
+            return;
+        }
+        
+        let old = core::mem::replace(&mut self.slots, vec![None; wanted]);
+        for id in old.into_iter().flatten() {
+            if let Some(name) = self.get(id) {
+                let slot = self.find_slot(name);
+                self.slots[slot] = Some(id);
+            }
+        }
+    }
+}
+
+impl Index<usize> for NameInterner {
+    type Output = str;
+    
+    fn index(&self, idx: usize) -> &str {
+        let (start, end) = self.spans[idx];
+        &self.arena[start..end]
+    }
+}
+
+/// 64-bit FNV-1a (names are short, and the index is rebuilt on every parse)
+fn hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
@@ -1,9 +1,29 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 pub mod simulation;
+#[cfg(feature = "std")]
 pub mod parser;
+#[cfg(feature = "std")]
 pub mod cli;
 pub mod engine;
 pub mod rng;
+pub mod bitset;
+pub mod interner;
+#[cfg(feature = "std")]
+pub mod output;
+pub mod analysis;
+pub mod sweep;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
 mod types;
+mod checkpoint;
+#[cfg(not(feature = "std"))]
+mod prelude;
 
-pub use types::{SimulationConfig, ColonyId, AntId, Direction};
\ No newline at end of file
+pub use types::{SimulationConfig, SimulationConfigBuilder, ColonyId, AntId, Direction, OutputFormat, ColorChoice, ColonySort, CollisionRule, DestroyedTargetPolicy, PerfThresholds, SpawnMode, UNLIMITED_MOVES};
\ No newline at end of file
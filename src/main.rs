@@ -1,83 +1,571 @@
+use std::io::{BufRead, IsTerminal, Write};
 use std::time::Duration;
-use ant_mania::{simulation::Simulation, engine::SimulationResult, cli};
+use ant_mania::{simulation::{MapMetadata, Simulation}, engine::{DeathCause, PhaseTimes, SimulationResult, TerminationReason}, cli::{self, CliOptions}, output, AntId, ColorChoice, ColonySort, OutputFormat, PerfThresholds, SimulationConfig, UNLIMITED_MOVES};
 
 fn main() {
-    println!("Ant Mania Simulation");
-    
     // Parse command line arguments
-    let config = match cli::parse_args() {
-        Ok(config) => config,
+    let (mut config, options) = match cli::parse_args() {
+        Ok(parsed) => parsed,
         Err(e) => {
             eprintln!("Error: {e}");
             std::process::exit(1);
         }
     };
     
-    println!("Configuration:");
-    println!("  Ants: {}", config.num_ants);
-    println!("  Map: {}", config.map_file);
-    println!("  Max moves: {}", config.max_moves);
-    if let Some(seed) = config.seed {
-        println!("  Seed: {seed}");
+    // Progress chatter is only printed alongside human-readable results
+    let text_output = options.output_format == OutputFormat::Text && !options.validate_only;
+    if text_output {
+        print_configuration(&config);
     }
-    println!();
     
     let mut sim = Simulation::new(config.clone());
     
     // Parse map file
-    match ant_mania::parser::parse_map_file(&mut sim, &config.map_file) {
+    let parse_start = std::time::Instant::now();
+    let parsed = if config.extra_map_files.is_empty() {
+        ant_mania::parser::parse_map_file(&mut sim, &config.map_file)
+    } else {
+        let paths: Vec<&str> = std::iter::once(config.map_file.as_str())
+            .chain(config.extra_map_files.iter().map(String::as_str))
+            .collect();
+        ant_mania::parser::parse_map_files(&mut sim, &paths)
+    };
+    let parse_time = parse_start.elapsed();
+    match parsed {
+        Ok(()) if options.validate_only => validate_map(&sim),
         Ok(()) => {
-            println!("Successfully parsed map with {} colonies", sim.num_colonies());
+            if text_output {
+                println!("Successfully parsed map with {} colonies", sim.num_colonies());
+                if let Some(metadata) = sim.metadata() {
+                    print_metadata(metadata);
+                }
+            }
+            
+            // The ant count for `--density` depends on the colony count, known only now
+            if let Some(density) = config.ant_density {
+                config.num_ants = ants_for_density(sim.num_colonies(), density);
+                sim.config.num_ants = config.num_ants;
+            }
+            
+            if options.warn_isolated {
+                report_isolated(&sim);
+            }
+            
+            if options.check_connectivity {
+                report_connectivity(&sim);
+            }
+            
+            if options.degree_histogram {
+                print_degree_histogram(&sim);
+            }
+            
+            if options.echo_map {
+                println!("\n=== Parsed Map ===");
+                for line in sim.map_lines(ColonySort::Name) {
+                    println!("{line}");
+                }
+            }
+            
+            if options.dry_run {
+                print_map_summary(&sim);
+                return;
+            }
+            
+            // Without at least two colonies no collision can ever happen
+            match sim.num_colonies() {
+                0 => {
+                    eprintln!("Error: map file contains no colonies");
+                    std::process::exit(1);
+                }
+                1 => eprintln!("Warning: map has only one colony, so no collisions are possible"),
+                _ => {}
+            }
+            
+            if !options.seed_list.is_empty() {
+                run_seed_list(&mut sim, &config, &options);
+                return;
+            }
+            
+            if options.repeat > 0 {
+                run_repeated(&mut sim, &config, &options);
+                return;
+            }
             
             // Initialize ants
-            sim.initialize_ants(config.num_ants);
-            println!("Initialized {} ants", sim.num_ants());
+            let init_start = std::time::Instant::now();
+            if let Err(e) = sim.initialize_ants(config.num_ants) {
+                eprintln!("Error placing ants: {e}");
+                std::process::exit(1);
+            }
+            let init_time = init_start.elapsed();
+            if options.require_connected {
+                report_ant_components(&sim);
+            }
+            if text_output {
+                println!("Initialized {} ants", sim.num_ants());
+                println!("Starting simulation...");
+            }
+            
+            if options.interactive {
+                run_interactive(&mut sim);
+                return;
+            }
             
             // Run simulation
-            println!("Starting simulation...");
             let start_time = std::time::Instant::now();
             
-            let result = sim.run_simulation();
+            let result = match &options.record_path {
+                Some(path) => run_recorded(&mut sim, path),
+                None => sim.run_simulation(),
+            };
             
             let elapsed = start_time.elapsed();
-            println!("Simulation completed in {elapsed:?}");
+            warn_if_truncated(result.termination, &config);
             
             // Output results
-            print_results(&result);
+            match options.output_format {
+                OutputFormat::Text => {
+                    println!("Simulation completed in {elapsed:?}");
+                    print_results(&result, &config, &options, &sim);
+                    
+                    // Performance summary
+                    print_performance_summary(&result, elapsed, sim.num_colonies(), config.num_ants, options.perf_thresholds);
+                    if config.profile {
+                        print_profile(&sim.phase_times, parse_time, init_time, elapsed);
+                    }
+                    if config.snapshot_every.is_some() && options.snapshot_path.is_none() {
+                        println!("\n=== Occupancy Snapshots ===");
+                        if let Err(e) = output::write_snapshots_csv(&sim, &mut std::io::stdout().lock()) {
+                            eprintln!("Error writing snapshots: {e}");
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                OutputFormat::Csv => {
+                    if let Err(e) = output::write_csv(&result, &mut std::io::stdout().lock()) {
+                        eprintln!("Error writing CSV: {e}");
+                        std::process::exit(1);
+                    }
+                }
+                OutputFormat::Counts => {
+                    if let Err(e) = output::write_counts(&result, &mut std::io::stdout().lock()) {
+                        eprintln!("Error writing counts: {e}");
+                        std::process::exit(1);
+                    }
+                }
+            }
             
-            // Performance summary
-            print_performance_summary(&result, elapsed, sim.num_colonies(), config.num_ants);
+            if let Some(path) = &options.snapshot_path {
+                write_snapshots(&sim, path);
+            }
         }
         Err(e) => {
-            eprintln!("Error parsing map: {e:?}");
+            eprintln!("Error parsing map: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Run the simulation while writing a replay log to `path`
+fn run_recorded(sim: &mut Simulation, path: &str) -> SimulationResult {
+    let recorded = std::fs::File::create(path).and_then(|file| {
+        let mut out = std::io::BufWriter::new(file);
+        let result = sim.run_simulation_recorded(&mut out)?;
+        out.flush()?;
+        Ok(result)
+    });
+    recorded.unwrap_or_else(|e| {
+        eprintln!("Error writing replay log {path}: {e}");
+        std::process::exit(1);
+    })
+}
+
+/// `--interactive`: step the simulation by commands read from stdin until `quit` or end of input
+fn run_interactive(sim: &mut Simulation) {
+    const COMMANDS: &str = "step [N], state, ants, quit";
+    
+    sim.warm_up(sim.config.warmup);
+    let mut destructions = std::mem::take(&mut sim.spawn_destructions);
+    for destruction in &destructions {
+        println!("{destruction}");
+    }
+    println!("Commands: {COMMANDS}");
+    
+    let mut stdin = std::io::stdin().lock();
+    let mut line = String::new();
+    loop {
+        print!("> ");
+        let _ = std::io::stdout().flush();
+        line.clear();
+        match stdin.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (None, _) => {}
+            (Some("step"), count) => {
+                let Ok(steps) = count.map_or(Ok(1), str::parse::<u32>) else {
+                    println!("Usage: step [N]");
+                    continue;
+                };
+                for _ in 0..steps {
+                    if let Some(reason) = sim.termination_reason() {
+                        println!("Simulation over: {reason}");
+                        break;
+                    }
+                    destructions.clear();
+                    let moves = sim.step(&mut destructions);
+                    println!("Iteration {}: {moves} moves, {} ants alive", sim.iteration, sim.num_living_ants());
+                    for destruction in &destructions {
+                        println!("  {destruction}");
+                    }
+                }
+            }
+            (Some("state"), None) => {
+                if sim.num_valid_colonies() == 0 {
+                    println!("(every colony has been destroyed)");
+                }
+                print!("{sim}");
+            }
+            (Some("ants"), None) => {
+                for (ant_id, colony_id) in sim.living_ants() {
+                    println!("Ant {ant_id}: {}", &sim.colony_names[colony_id as usize]);
+                }
+            }
+            (Some("quit" | "exit"), None) => break,
+            _ => println!("Unknown command: {} (commands: {COMMANDS})", line.trim()),
+        }
+    }
+}
+
+/// Write the occupancy snapshots to `path`, as JSON for a `.json` file and CSV otherwise
+fn write_snapshots(sim: &Simulation, path: &str) {
+    let written = std::fs::File::create(path).and_then(|file| {
+        let mut out = std::io::BufWriter::new(file);
+        if path.ends_with(".json") {
+            output::write_snapshots_json(sim, &mut out)?;
+        } else {
+            output::write_snapshots_csv(sim, &mut out)?;
+        }
+        out.flush()
+    });
+    if let Err(e) = written {
+        eprintln!("Error writing snapshots {path}: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn print_configuration(config: &SimulationConfig) {
+    println!("Ant Mania Simulation");
+    println!("Configuration:");
+    match config.ant_density {
+        Some(density) => println!("  Ants: {density} per colony"),
+        None => println!("  Ants: {}", config.num_ants),
+    }
+    println!("  Map: {}", config.map_file);
+    match config.max_moves_range {
+        Some((min, max)) => println!("  Max moves: {min}-{max} per ant"),
+        None if config.max_moves == UNLIMITED_MOVES => println!("  Max moves: unlimited"),
+        None => println!("  Max moves: {}", config.max_moves),
+    }
+    if config.warmup > 0 {
+        println!("  Warmup: {} iterations", config.warmup);
+    }
+    if let Some(seed) = config.seed {
+        println!("  Seed: {seed}");
+    }
+    if config.no_backtrack {
+        println!("  No backtrack: enabled");
+    }
+    if config.direction_weights.iter().any(|&w| w != config.direction_weights[0]) {
+        println!("  Direction weights (N,S,E,W,U,D): {:?}", config.direction_weights);
+    }
+    println!();
+}
+
+/// `--validate-only`: report map problems to stderr and exit with status 1, or stay silent
+fn validate_map(sim: &Simulation) {
+    let mut problems: Vec<String> = sim.validate().iter().map(ToString::to_string).collect();
+    if sim.num_colonies() == 0 {
+        problems.push("map file contains no colonies".to_string());
+    }
+    
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("Error: {problem}");
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Round `num_colonies * density` to an ant count, clamped to the largest representable count
+fn ants_for_density(num_colonies: usize, density: f64) -> AntId {
+    let wanted = (num_colonies as f64 * density).round();
+    if wanted > AntId::MAX as f64 {
+        eprintln!("Warning: density {density} asks for {wanted} ants, clamping to {}", AntId::MAX);
+        AntId::MAX
+    } else {
+        wanted as AntId
+    }
+}
+
+fn report_isolated(sim: &Simulation) {
+    let isolated = sim.isolated_colonies();
+    if !isolated.is_empty() {
+        eprintln!("Warning: {} colonies have no connections:", isolated.len());
+        for colony_id in isolated {
+            eprintln!("  {}", &sim.colony_names[colony_id as usize]);
+        }
+    }
+}
+
+fn report_connectivity(sim: &Simulation) {
+    let components = sim.connected_components();
+    if components.len() > 1 {
+        let sizes: Vec<String> = components.iter().map(|c| c.len().to_string()).collect();
+        eprintln!("Warning: map has {} disconnected components (sizes: {})", components.len(), sizes.join(", "));
+    } else {
+        println!("Map is fully connected");
+    }
+}
+
+/// `--require-connected`: warn when the placed ants can't all reach each other
+fn report_ant_components(sim: &Simulation) {
+    let components = sim.living_ant_components();
+    if components > 1 {
+        eprintln!("Warning: ants start in {components} disconnected components, so ants in different components can never collide");
+    }
+}
+
+fn print_degree_histogram(sim: &Simulation) {
+    let histogram = sim.degree_histogram();
+    // Always show degrees 0-4; up/down rows only when the map uses them
+    let last = histogram.iter().rposition(|&count| count > 0).unwrap_or(0).max(4);
+    println!("\n=== Degree Histogram ===");
+    println!("Degree  Colonies");
+    for (degree, count) in histogram.iter().enumerate().take(last + 1) {
+        println!("{degree:>6}  {count:>8}");
+    }
+}
+
+/// The library only reports hitting `max_iterations` in the result, so warn about it here
+fn warn_if_truncated(termination: TerminationReason, config: &SimulationConfig) {
+    if termination == TerminationReason::IterationLimitExceeded {
+        eprintln!("Warning: Simulation reached {} iterations, terminating", config.max_iterations);
+    }
+}
+
+/// Run the map once per seed in `options.seed_list`, printing one summary line per seed
+fn run_seed_list(sim: &mut Simulation, config: &SimulationConfig, options: &CliOptions) {
+    let mut destructions = Vec::new();
+    for &seed in &options.seed_list {
+        sim.reset_ants(seed);
+        if let Err(e) = sim.initialize_ants(config.num_ants) {
+            eprintln!("Error placing ants: {e}");
             std::process::exit(1);
         }
+        
+        destructions.clear();
+        let result = sim.run_simulation_into(&mut destructions);
+        warn_if_truncated(result.termination, config);
+        println!("seed {seed}: {} iterations, {} destructions, {} survivors",
+                 result.iterations, destructions.len(), result.surviving_colonies.len());
     }
 }
 
-fn print_results(result: &SimulationResult) {
+/// Run the same map `options.repeat` times with consecutive seeds and print aggregate statistics
+fn run_repeated(sim: &mut Simulation, config: &SimulationConfig, options: &CliOptions) {
+    let base_seed = config.seed.unwrap_or_else(ant_mania::simulation::default_seed);
+    let runs = options.repeat;
+    
+    let mut total_iterations = 0u64;
+    let mut min_iterations = u32::MAX;
+    let mut max_iterations = 0;
+    let mut total_destructions = 0u64;
+    let mut fully_destroyed = 0;
+    
+    // One destruction buffer reused across runs
+    let mut destructions = Vec::new();
+    let start_time = std::time::Instant::now();
+    for run in 0..runs {
+        let seed = base_seed.wrapping_add(run as u64);
+        sim.reset_ants(seed);
+        if let Err(e) = sim.initialize_ants(config.num_ants) {
+            eprintln!("Error placing ants: {e}");
+            std::process::exit(1);
+        }
+        
+        destructions.clear();
+        let result = sim.run_simulation_into(&mut destructions);
+        warn_if_truncated(result.termination, config);
+        if options.verbose {
+            println!("Run {} (seed {seed}): {} iterations, {} destructions, {} survivors",
+                     run + 1, result.iterations, destructions.len(), result.surviving_colonies.len());
+        }
+        
+        total_iterations += result.iterations as u64;
+        min_iterations = min_iterations.min(result.iterations);
+        max_iterations = max_iterations.max(result.iterations);
+        total_destructions += destructions.len() as u64;
+        if result.surviving_colonies.is_empty() {
+            fully_destroyed += 1;
+        }
+    }
+    
+    println!("\n=== Aggregate Results ({runs} runs, seeds {base_seed}..={}) ===", base_seed.wrapping_add(runs as u64 - 1));
+    println!("Iterations: mean {:.1}, min {min_iterations}, max {max_iterations}", total_iterations as f64 / runs as f64);
+    println!("Destructions: mean {:.2}", total_destructions as f64 / runs as f64);
+    println!("All colonies destroyed: {fully_destroyed}/{runs} runs");
+    println!("Total runtime: {:?}", start_time.elapsed());
+}
+
+fn print_metadata(metadata: &MapMetadata) {
+    if let Some(name) = &metadata.name {
+        println!("Map name: {name}");
+    }
+    if let Some(version) = &metadata.version {
+        println!("Map version: {version}");
+    }
+    for (key, value) in &metadata.fields {
+        println!("Map {key}: {value}");
+    }
+}
+
+fn print_map_summary(sim: &Simulation) {
+    let stats = sim.stats();
+    println!("\n=== Map Summary ===");
+    println!("Colonies: {}", stats.colonies);
+    println!("Total edges: {} tunnels ({} directed connections)", stats.total_edges, sim.total_connections());
+    println!("Trapped colonies (no neighbors): {}", stats.isolated_colonies);
+    println!("Degree: max {}, average {:.2}", stats.max_degree, stats.avg_degree);
+    println!("Connected components: {}", sim.connected_components().len());
+}
+
+/// Whether text results get ANSI colors: `auto` only on a terminal without `NO_COLOR` set
+fn use_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+    }
+}
+
+fn print_results(result: &SimulationResult, config: &SimulationConfig, options: &CliOptions, sim: &Simulation) {
+    // ANSI red for destructions and green for survivors, or the plain text
+    let color = use_color(options.color);
+    let paint = |text: String, code: &str| if color { format!("\x1b[{code}m{text}\x1b[0m") } else { text };
+    
     println!("\n=== Simulation Results ===");
     println!("Iterations: {}", result.iterations);
+    println!("Terminated because {}", result.termination);
     println!("Total ant moves: {}", result.total_moves);
-    println!("Colonies destroyed: {}", result.destructions.len());
-    println!("Colonies surviving: {}", result.surviving_colonies.len());
+    if config.destroy_on_collision {
+        println!("Colonies destroyed: {}", result.destruction_count());
+    }
+    // Under the default rule every collision destroys a colony, so only show the count when they differ
+    if result.total_collisions != result.destruction_count() as u64 {
+        println!("Collisions: {}", result.total_collisions);
+    }
+    println!("Colonies surviving: {}", result.survivor_count());
     
     if !result.destructions.is_empty() {
         println!("\nDestruction events:");
         for destruction in &result.destructions {
-            println!("{destruction}");
+            let message = match &options.message_format {
+                Some(template) => output::format_destruction(destruction, template),
+                None => destruction.to_string(),
+            };
+            println!("{}", paint(message, "31"));
+        }
+    }
+    
+    if !result.captures.is_empty() {
+        println!("\nCapture events:");
+        for capture in &result.captures {
+            println!("{capture}");
         }
     }
     
     if !result.surviving_colonies.is_empty() {
         println!("\nFinal map state:");
         for colony in &result.surviving_colonies {
-            println!("{colony}");
+            println!("{}", paint(colony.clone(), "32"));
+        }
+    }
+    
+    if let Some(cols) = options.ascii_grid {
+        println!("\nFinal grid (. surviving, # destroyed):");
+        print!("{}", output::ascii_grid(sim, cols));
+    }
+    
+    if !result.colony_visits.is_empty() {
+        println!("\nColony visits (heatmap):");
+        for (name, visits) in &result.colony_visits {
+            println!("{name}: {visits}");
+        }
+    }
+    
+    if !result.oscillations.is_empty() {
+        println!("\nOscillating ants:");
+        for &(ant_id, a, b) in &result.oscillations {
+            println!("Ant {ant_id}: {} <-> {}", &sim.colony_names[a as usize], &sim.colony_names[b as usize]);
         }
     }
+    
+    let moves = &result.move_distribution;
+    if moves.ants > 0 {
+        println!("\nMoves per ant: min {}, max {}, mean {:.1}", moves.min, moves.max, moves.mean);
+        for (bucket, &count) in moves.histogram.iter().enumerate() {
+            let low = bucket as u32 * moves.bucket_width;
+            if low <= moves.max {
+                println!("  {:>5}-{:<5} {count}", low, low + moves.bucket_width - 1);
+            }
+        }
+    }
+    
+    if let Some(ant_id) = config.trace_ant {
+        println!("\nTrace of ant {ant_id}:");
+        if ant_id as usize >= sim.num_ants() {
+            println!("(no such ant: there are only {} ants)", sim.num_ants());
+        }
+        for line in &sim.ant_trace {
+            println!("{line}");
+        }
+    }
+    
+    if result.death_causes.iter().any(Option::is_some) {
+        println!("\nAnt deaths:");
+        for (ant_id, cause) in result.death_causes.iter().enumerate() {
+            let Some(cause) = cause else {
+                continue;
+            };
+            let colony_name = &sim.colony_names[cause.colony() as usize];
+            match cause {
+                DeathCause::Collision(_) => println!("Ant {ant_id}: collision in {colony_name}"),
+                DeathCause::EnteredDestroyedColony(_) => println!("Ant {ant_id}: entered destroyed {colony_name}"),
+                DeathCause::ColonyDestroyedUnderneath(_) => println!("Ant {ant_id}: {colony_name} destroyed underneath it"),
+                DeathCause::LostCapture(_) => println!("Ant {ant_id}: lost a fight for {colony_name}"),
+            }
+        }
+    }
+}
+
+fn print_profile(phases: &PhaseTimes, parse_time: Duration, init_time: Duration, simulation_time: Duration) {
+    println!("\n=== Profile ===");
+    println!("Parse: {parse_time:?}");
+    println!("Ant placement: {init_time:?}");
+    println!("Simulation: {simulation_time:?}");
+    if phases.steps > 0 {
+        println!("  Phase 1 (choose moves): {:?} total, {:.2}μs per step",
+                 phases.calculate, phases.calculate.as_nanos() as f64 / phases.steps as f64 / 1000.0);
+        println!("  Phase 2 (apply moves): {:?} total, {:.2}μs per step",
+                 phases.apply, phases.apply.as_nanos() as f64 / phases.steps as f64 / 1000.0);
+    }
 }
 
-fn print_performance_summary(result: &SimulationResult, elapsed: Duration, num_colonies: usize, num_ants: u16) {
+fn print_performance_summary(result: &SimulationResult, elapsed: Duration, num_colonies: usize, num_ants: AntId, perf_thresholds: Option<PerfThresholds>) {
     println!("\n=== Performance Summary ===");
     println!("Total runtime: {elapsed:?}");
     println!("Colonies processed: {num_colonies}");
@@ -93,21 +581,13 @@ fn print_performance_summary(result: &SimulationResult, elapsed: Duration, num_c
         println!("Average time per ant move: {avg_move_time:.2}ns");
     }
     
-    // Performance classification
-    let runtime_ms = elapsed.as_micros() as f64 / 1000.0;
-    if num_colonies <= 50 {
-        // Small map
-        if runtime_ms < 100.0 {
-            println!("✅ Excellent performance (target: <100ms)");
-        } else {
-            println!("⚠️  Performance below target (<100ms for small maps)");
-        }
-    } else {
-        // Medium/large map
-        if runtime_ms < 1000.0 {
-            println!("✅ Excellent performance (target: <1s for large maps)");
+    // Performance classification (only when asked for; the right targets depend on the hardware)
+    if let Some(thresholds) = perf_thresholds {
+        let target = thresholds.target_for(num_colonies);
+        if elapsed < target {
+            println!("✅ Excellent performance (target: <{target:?})");
         } else {
-            println!("⚠️  Performance below target (<1s for large maps)");
+            println!("⚠️  Performance below target (<{target:?} for {num_colonies} colonies)");
         }
     }
 }
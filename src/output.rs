@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+use crate::engine::{DestructionEvent, ReplayEvent, SimulationResult};
+use crate::simulation::Simulation;
+
+/// Default `--message-format` template, matching `DestructionEvent`'s `Display`
+pub const DEFAULT_MESSAGE_FORMAT: &str = "{colony} has been destroyed by ant {ant_a} and ant {ant_b}!";
+
+/// Placeholders understood by `format_destruction`
+const MESSAGE_PLACEHOLDERS: [&str; 4] = ["colony", "ant_a", "ant_b", "iteration"];
+
+/// Write destruction events as CSV (`iteration,colony,ant_a,ant_b`), followed by a blank line
+/// and a second section listing each surviving colony line
+pub fn write_csv<W: Write>(result: &SimulationResult, out: &mut W) -> io::Result<()> {
+    writeln!(out, "iteration,colony,ant_a,ant_b")?;
+    for event in &result.destructions {
+        writeln!(out, "{},{},{},{}", event.iteration, csv_field(&event.colony_name), event.ant_a, event.ant_b)?;
+    }
+    
+    writeln!(out)?;
+    writeln!(out, "surviving_colony")?;
+    for colony in &result.surviving_colonies {
+        writeln!(out, "{}", csv_field(colony))?;
+    }
+    
+    Ok(())
+}
+
+/// Write `iterations\tdestructions\tsurvivors` as a single line, for appending to a results file
+pub fn write_counts<W: Write>(result: &SimulationResult, out: &mut W) -> io::Result<()> {
+    writeln!(out, "{}\t{}\t{}", result.iterations, result.destruction_count(), result.survivor_count())
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break (RFC 4180)
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Write a destruction event as one line of JSON, e.g.
+/// `{"iteration":3,"colony":"Fizz","ant_a":4,"ant_b":17}`
+pub fn write_destruction_json<W: Write>(event: &DestructionEvent, out: &mut W) -> io::Result<()> {
+    writeln!(out, "{{\"iteration\":{},\"colony\":{},\"ant_a\":{},\"ant_b\":{}}}",
+             event.iteration, json_string(&event.colony_name), event.ant_a, event.ant_b)
+}
+
+/// Quote and escape a string as a JSON string literal
+pub fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Lay colonies out row-major by id, `cols` per row: `.` for a surviving colony, `#` for a destroyed one
+/// Only meaningful for grid maps whose lines are in row-major order; the last row may be short
+pub fn ascii_grid(sim: &Simulation, cols: usize) -> String {
+    let cols = cols.max(1);
+    let mut grid = String::with_capacity(sim.num_colonies() + sim.num_colonies() / cols + 1);
+    for idx in 0..sim.num_colonies() {
+        grid.push(if sim.colony_valid.get(idx) { '.' } else { '#' });
+        if (idx + 1) % cols == 0 || idx + 1 == sim.num_colonies() {
+            grid.push('\n');
+        }
+    }
+    grid
+}
+
+/// Write the start of a replay log: the colony table and every ant's starting colony
+///
+/// The log is line-based. `colonies N` is followed by N `ID NAME` lines and `ants M` by
+/// M `ANT COLONY` lines; each step then writes `step I` followed by `m ANT FROM TO` for
+/// every move and `d ANT` for every death, in the order they happened. Replaying the
+/// lines from the start reproduces the exact ant positions after any step.
+pub fn write_replay_header<W: Write>(sim: &Simulation, out: &mut W) -> io::Result<()> {
+    writeln!(out, "colonies {}", sim.num_colonies())?;
+    for (id, name) in sim.colony_names.iter().enumerate() {
+        writeln!(out, "{id} {name}")?;
+    }
+    
+    writeln!(out, "ants {}", sim.num_ants())?;
+    for (ant, colony) in sim.ant_colonies.iter().enumerate() {
+        writeln!(out, "{ant} {colony}")?;
+    }
+    
+    Ok(())
+}
+
+/// Write the moves and deaths of the step the simulation just completed
+pub fn write_replay_step<W: Write>(sim: &Simulation, out: &mut W) -> io::Result<()> {
+    writeln!(out, "step {}", sim.iteration)?;
+    for event in &sim.replay_events {
+        match event {
+            ReplayEvent::Move { ant, from, to } => writeln!(out, "m {ant} {from} {to}")?,
+            ReplayEvent::Death { ant } => writeln!(out, "d {ant}")?,
+        }
+    }
+    Ok(())
+}
+
+/// Write occupancy snapshots as CSV: an `iteration` column then one column per colony (by name),
+/// with one row of ant counts per snapshot
+pub fn write_snapshots_csv<W: Write>(sim: &Simulation, out: &mut W) -> io::Result<()> {
+    write!(out, "iteration")?;
+    for name in sim.colony_names.iter() {
+        write!(out, ",{}", csv_field(name))?;
+    }
+    writeln!(out)?;
+    
+    for (iteration, counts) in &sim.occupancy_snapshots {
+        write!(out, "{iteration}")?;
+        for count in counts {
+            write!(out, ",{count}")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Write occupancy snapshots as one JSON object, e.g.
+/// `{"colonies":["A","B"],"snapshots":[{"iteration":5,"ants":[1,0]}]}`
+pub fn write_snapshots_json<W: Write>(sim: &Simulation, out: &mut W) -> io::Result<()> {
+    let names: Vec<String> = sim.colony_names.iter().map(json_string).collect();
+    write!(out, "{{\"colonies\":[{}],\"snapshots\":[", names.join(","))?;
+    for (i, (iteration, counts)) in sim.occupancy_snapshots.iter().enumerate() {
+        let counts: Vec<String> = counts.iter().map(ToString::to_string).collect();
+        let separator = if i == 0 { "" } else { "," };
+        write!(out, "{separator}{{\"iteration\":{iteration},\"ants\":[{}]}}", counts.join(","))?;
+    }
+    writeln!(out, "]}}")
+}
+
+/// Render a destruction event with a template containing `{colony}`, `{ant_a}`, `{ant_b}`
+/// and `{iteration}` placeholders; any other text is copied verbatim
+pub fn format_destruction(event: &DestructionEvent, template: &str) -> String {
+    let mut out = String::with_capacity(template.len() + event.colony_name.len());
+    let mut rest = template;
+    
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+        
+        match &after[..close] {
+            "colony" => out.push_str(&event.colony_name),
+            "ant_a" => out.push_str(&event.ant_a.to_string()),
+            "ant_b" => out.push_str(&event.ant_b.to_string()),
+            "iteration" => out.push_str(&event.iteration.to_string()),
+            _ => {
+                // Not a placeholder: keep the brace and continue scanning after it
+                out.push('{');
+                rest = after;
+                continue;
+            }
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    
+    out
+}
+
+/// Check that every `{...}` in a message template is a known placeholder
+pub fn validate_message_format(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let close = after.find('}').ok_or_else(|| "unclosed '{'".to_string())?;
+        let name = &after[..close];
+        if !MESSAGE_PLACEHOLDERS.contains(&name) {
+            return Err(format!("unknown placeholder {{{name}}}"));
+        }
+        rest = &after[close + 1..];
+    }
+    
+    Ok(())
+}
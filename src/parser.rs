@@ -1,73 +1,492 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::collections::HashMap;
-use crate::types::{ColonyId, Direction};
-use crate::simulation::Simulation;
+use crate::types::{ColonyId, Direction, SimulationConfig};
+use crate::simulation::{MapMetadata, Simulation};
+use crate::engine::is_separator;
 
-pub fn parse_map_file(simulation: &mut Simulation, file_path: &str) -> Result<(), String> {
-    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {e}"))?;
-    let reader = BufReader::new(file);
+/// Errors produced while reading or parsing a map
+/// `line` is the 1-based line number (or entry number for JSON maps)
+#[derive(Debug)]
+pub enum ParseMapError {
+    Io(std::io::Error),
+    InvalidDirection { line: usize, token: String },
+    InvalidConnection { line: usize, token: String },
+    DuplicateColony { line: usize, name: String },
+    UnknownNeighbor { line: usize, name: String },
+    TooManyColonies { count: usize, max: usize },
+    UnterminatedQuote { line: usize },
+    InvalidCapacity { line: usize, token: String },
+    InvalidMetadata { line: usize, token: String },
+    MisplacedMetadata { line: usize },
+    InvalidJson(String),
+    InFile { path: String, error: Box<ParseMapError> },   // Error in one of several merged files
+}
+
+impl std::fmt::Display for ParseMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseMapError::Io(e) => write!(f, "Failed to read map: {}", e),
+            ParseMapError::InvalidDirection { line, token } => write!(f, "line {}: Invalid direction: {}", line, token),
+            ParseMapError::InvalidConnection { line, token } => write!(f, "line {}: Invalid connection format: {}", line, token),
+            ParseMapError::DuplicateColony { line, name } => write!(f, "line {}: Duplicate colony: {}", line, name),
+            ParseMapError::UnknownNeighbor { line, name } => write!(f, "line {}: Unknown neighbor colony: {}", line, name),
+            ParseMapError::TooManyColonies { count, max } => write!(f, "map has {} colonies, exceeds maximum {}", count, max),
+            ParseMapError::UnterminatedQuote { line } => write!(f, "line {}: unterminated quoted name", line),
+            ParseMapError::InvalidCapacity { line, token } => write!(f, "line {}: Invalid colony capacity: {} (expected 1-255)", line, token),
+            ParseMapError::InvalidMetadata { line, token } => write!(f, "line {}: Invalid metadata: {} (expected %key: value)", line, token),
+            ParseMapError::MisplacedMetadata { line } => write!(f, "line {}: metadata (%key: value) must come before the first colony", line),
+            ParseMapError::InvalidJson(msg) => write!(f, "Invalid JSON map: {}", msg),
+            ParseMapError::InFile { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for ParseMapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseMapError::Io(e) => Some(e),
+            ParseMapError::InFile { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl ParseMapError {
+    fn in_file(self, path: &str) -> Self {
+        ParseMapError::InFile { path: path.to_string(), error: Box::new(self) }
+    }
+}
+
+impl From<std::io::Error> for ParseMapError {
+    fn from(e: std::io::Error) -> Self {
+        ParseMapError::Io(e)
+    }
+}
+
+/// Colony name, its (direction, neighbor name) connections and source line, before ids are assigned
+#[cfg(feature = "json")]
+type RawColony = (String, Vec<(Direction, String, bool)>, usize);
+
+pub fn parse_map_file(simulation: &mut Simulation, file_path: &str) -> Result<(), ParseMapError> {
+    #[cfg(feature = "json")]
+    if file_path.ends_with(".json") {
+        let contents = std::fs::read_to_string(file_path)?;
+        return parse_map_json(simulation, &contents);
+    }
     
-    // First pass: collect all colony names to assign IDs
-    let mut temp_colonies: Vec<(String, Vec<(Direction, String)>)> = Vec::new();
+    // First pass: validate syntax and assign ids; only the colony names and header are kept
+    let scan = scan_file(file_path)?;
+    simulation.metadata = scan.metadata;
+    intern_colonies(simulation, scan.names)?;
     
-    for line in reader.lines() {
-        let line = line.map_err(|e| format!("Failed to read line: {e}"))?;
-        let line = line.trim();
-        
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
+    // Second pass: re-read the file and resolve neighbors straight into the graph
+    resolve_file(simulation, file_path, 0)?;
+    
+    // Update valid direction bitmasks for all colonies
+    simulation.update_all_valid_directions();
+    
+    Ok(())
+}
+
+/// Parse several plain-text (or `.gz`) map files into one graph, e.g. one file per region
+/// Neighbors may refer to colonies in any of the files; colony names must be unique across all of them.
+/// Ids are assigned in file order, then line order. Errors name the offending file.
+/// The metadata header is taken from the first file that has one.
+pub fn parse_map_files(simulation: &mut Simulation, file_paths: &[&str]) -> Result<(), ParseMapError> {
+    // First pass over every file, so cross-file references can be resolved afterwards
+    let mut names_per_file = Vec::with_capacity(file_paths.len());
+    simulation.metadata = None;
+    for &path in file_paths {
+        let scan = scan_file(path).map_err(|e| e.in_file(path))?;
+        if simulation.metadata.is_none() {
+            simulation.metadata = scan.metadata;
         }
-        
-        // Parse line format: "ColonyName direction=Neighbor direction=Neighbor..."
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.is_empty() {
-            continue;
+        names_per_file.push(scan.names);
+    }
+    
+    size_colonies(simulation, names_per_file.iter().map(Vec::len).sum())?;
+    let mut first_id = 0;
+    for (&path, names) in file_paths.iter().zip(names_per_file) {
+        let count = names.len();
+        intern_names(simulation, first_id, names).map_err(|e| e.in_file(path))?;
+        first_id += count;
+    }
+    
+    // Second pass: resolve neighbors file by file
+    let mut first_id: ColonyId = 0;
+    for &path in file_paths {
+        first_id = resolve_file(simulation, path, first_id).map_err(|e| e.in_file(path))?;
+    }
+    
+    // Update valid direction bitmasks for all colonies
+    simulation.update_all_valid_directions();
+    
+    Ok(())
+}
+
+/// What the first pass keeps of a map: (colony name, line) pairs and the metadata header
+#[derive(Default)]
+struct Scan {
+    names: Vec<(String, usize)>,
+    metadata: Option<MapMetadata>,
+}
+
+impl Scan {
+    /// Validate one line, recording its colony name or header entry
+    fn line(&mut self, line: &str, line_number: usize) -> Result<(), ParseMapError> {
+        if let Some((key, value)) = metadata_entry(line, line_number)? {
+            if !self.names.is_empty() {
+                return Err(ParseMapError::MisplacedMetadata { line: line_number });
+            }
+            self.metadata.get_or_insert_with(MapMetadata::default).insert(key, value);
+        } else if let Some(colony_name) = scan_line(line, line_number)? {
+            self.names.push((colony_name.to_string(), line_number));
+        }
+        Ok(())
+    }
+}
+
+/// First pass over a whole file: validate syntax and collect colony names and the header
+fn scan_file(file_path: &str) -> Result<Scan, ParseMapError> {
+    let mut scan = Scan::default();
+    for (line_idx, line) in open_map_reader(file_path)?.lines().enumerate() {
+        scan.line(&line?, line_idx + 1)?;
+    }
+    Ok(scan)
+}
+
+/// Second pass over a whole file whose colonies were interned starting at `first_id`
+/// Returns the id following the file's last colony
+fn resolve_file(simulation: &mut Simulation, file_path: &str, first_id: ColonyId) -> Result<ColonyId, ParseMapError> {
+    let mut colony_id = first_id;
+    for (line_idx, line) in open_map_reader(file_path)?.lines().enumerate() {
+        if resolve_line(simulation, colony_id, &line?, line_idx + 1)? {
+            colony_id += 1;
+        }
+    }
+    Ok(colony_id)
+}
+
+/// Separator-delimited tokens of a map line (see `is_separator`), where a double-quoted section is kept whole
+/// Quotes only open at the start of a token or right after a `=`, so `"Sector=7"` and
+/// `north="Other Colony"` are single tokens while unquoted names are split exactly as before
+struct Tokens<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str, line_number: usize) -> Self {
+        Tokens { rest: line, line: line_number }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<&'a str, ParseMapError>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rest = self.rest.trim_start_matches(is_separator);
+        if self.rest.is_empty() {
+            return None;
         }
         
-        let colony_name = parts[0].to_string();
-        let mut connections = Vec::new();
-        
-        // Parse direction=neighbor pairs
-        for connection_str in &parts[1..] {
-            if let Some(eq_pos) = connection_str.find('=') {
-                let dir_str = &connection_str[..eq_pos];
-                let neighbor_name = &connection_str[eq_pos + 1..];
-                
-                let direction = dir_str.parse::<Direction>()
-                    .map_err(|_| format!("Invalid direction: {dir_str}"))?;
-                
-                connections.push((direction, neighbor_name.to_string()));
-            } else {
-                return Err(format!("Invalid connection format: {connection_str}"));
+        let mut end = self.rest.len();
+        let mut in_quote = false;
+        let mut prev = None;
+        for (i, c) in self.rest.char_indices() {
+            if in_quote {
+                in_quote = c != '"';
+            } else if is_separator(c) {
+                end = i;
+                break;
+            } else if c == '"' && (prev.is_none() || prev == Some('=')) {
+                in_quote = true;
             }
+            prev = Some(c);
+        }
+        
+        if in_quote {
+            self.rest = "";
+            return Some(Err(ParseMapError::UnterminatedQuote { line: self.line }));
         }
         
-        temp_colonies.push((colony_name, connections));
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(Ok(token))
+    }
+}
+
+/// Strip the surrounding double quotes from a quoted name
+fn unquote(name: &str) -> &str {
+    if name.len() >= 2 && name.starts_with('"') && name.ends_with('"') {
+        &name[1..name.len() - 1]
+    } else {
+        name
+    }
+}
+
+/// Split an optional `:K` capacity suffix off a colony's name token (`Hive:3`, `"Big Hive":3`)
+/// Only a non-empty run of digits after the last `:` counts, so other names containing `:` are unaffected
+fn split_capacity(token: &str, line: usize) -> Result<(&str, Option<u8>), ParseMapError> {
+    match token.rsplit_once(':') {
+        Some((name, digits)) if !name.is_empty() && !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) => {
+            let capacity = digits.parse::<u8>()
+                .ok()
+                .filter(|&capacity| capacity > 0)
+                .ok_or_else(|| ParseMapError::InvalidCapacity { line, token: token.to_string() })?;
+            Ok((name, Some(capacity)))
+        }
+        _ => Ok((token, None)),
+    }
+}
+
+/// Line content after any leading separators, with `#` comment lines reading as empty
+fn line_content(line: &str) -> &str {
+    let content = line.trim_start_matches(is_separator);
+    if content.starts_with('#') { "" } else { content }
+}
+
+/// Split a `%key: value` header line into its trimmed key and value, or `None` for any other line
+fn metadata_entry(line: &str, line_number: usize) -> Result<Option<(&str, &str)>, ParseMapError> {
+    let Some(entry) = line_content(line).strip_prefix('%') else {
+        return Ok(None);
+    };
+    match entry.split_once(':') {
+        Some((key, value)) if !key.trim_matches(is_separator).is_empty() => Ok(Some((key.trim_matches(is_separator), value.trim_matches(is_separator)))),
+        _ => Err(ParseMapError::InvalidMetadata { line: line_number, token: line_content(line).trim_end_matches(is_separator).to_string() }),
+    }
+}
+
+/// Split a `direction=Neighbor` token (the neighbor may be quoted), also returning whether it was
+/// written `direction>=Neighbor` to mark the tunnel as deliberately one-way
+fn parse_connection(token: &str, line: usize) -> Result<(Direction, &str, bool), ParseMapError> {
+    let (dir_str, neighbor_name) = token.split_once('=')
+        .ok_or_else(|| ParseMapError::InvalidConnection { line, token: token.to_string() })?;
+    let (dir_str, one_way) = match dir_str.strip_suffix('>') {
+        Some(dir_str) => (dir_str, true),
+        None => (dir_str, false),
+    };
+    let direction = dir_str.parse::<Direction>()
+        .map_err(|_| ParseMapError::InvalidDirection { line, token: dir_str.to_string() })?;
+    Ok((direction, unquote(neighbor_name), one_way))
+}
+
+/// First pass over a map line ("ColonyName direction=Neighbor ..."): validate its
+/// connections and return the colony name, or `None` for a blank or comment line
+fn scan_line(line: &str, line_number: usize) -> Result<Option<&str>, ParseMapError> {
+    let mut tokens = Tokens::new(line_content(line), line_number);
+    let Some(colony_name) = tokens.next() else {
+        return Ok(None);
+    };
+    let (colony_name, _) = split_capacity(colony_name?, line_number)?;
+    let colony_name = unquote(colony_name);
+    
+    for token in tokens {
+        parse_connection(token?, line_number)?;
     }
     
-    // Initialize simulation data structures
-    let num_colonies = temp_colonies.len();
+    Ok(Some(colony_name))
+}
+
+/// Size the simulation for the scanned colonies and intern their names, assigning ids in file order
+fn intern_colonies(simulation: &mut Simulation, names: Vec<(String, usize)>) -> Result<(), ParseMapError> {
+    size_colonies(simulation, names.len())?;
+    intern_names(simulation, 0, names)
+}
+
+/// Allocate the simulation's colony vectors for `num_colonies` colonies
+fn size_colonies(simulation: &mut Simulation, num_colonies: usize) -> Result<(), ParseMapError> {
+    // Colony ids must fit in ColonyId, otherwise the id casts would silently wrap
+    if num_colonies > ColonyId::MAX as usize {
+        return Err(ParseMapError::TooManyColonies { count: num_colonies, max: ColonyId::MAX as usize });
+    }
     simulation.initialize_with_capacity(num_colonies);
+    Ok(())
+}
+
+/// Intern names into `colony_names` with consecutive ids starting at `first_id`
+fn intern_names(simulation: &mut Simulation, first_id: usize, names: Vec<(String, usize)>) -> Result<(), ParseMapError> {
+    for (offset, (name, line)) in names.into_iter().enumerate() {
+        if simulation.colony_names.contains(&name) {
+            return Err(ParseMapError::DuplicateColony { line, name });
+        }
+        simulation.set_colony_name((first_id + offset) as ColonyId, &name);
+    }
+    Ok(())
+}
+
+/// Second pass over a map line: resolve its connections into the simulation's neighbor vectors
+/// Returns false for a blank, comment or header line, which doesn't consume a colony id
+fn resolve_line(simulation: &mut Simulation, colony_id: ColonyId, line: &str, line_number: usize) -> Result<bool, ParseMapError> {
+    let content = line_content(line);
+    if content.starts_with('%') {
+        return Ok(false);
+    }
+    let mut tokens = Tokens::new(content, line_number);
+    let Some(colony_name) = tokens.next().transpose()? else {
+        return Ok(false);
+    };
+    if let (_, Some(capacity)) = split_capacity(colony_name, line_number)? {
+        simulation.colony_capacity[colony_id as usize] = capacity;
+    }
+    
+    for token in tokens {
+        let (direction, neighbor_name, one_way) = parse_connection(token?, line_number)?;
+        let neighbor_id = simulation.colony_names.id_of(neighbor_name)
+            .ok_or_else(|| ParseMapError::UnknownNeighbor { line: line_number, name: neighbor_name.to_string() })?;
+        
+        simulation.set_neighbor(colony_id, direction, neighbor_id);
+        if one_way {
+            simulation.colony_one_way[colony_id as usize] |= direction.as_bit_mask();
+        }
+    }
+    
+    Ok(true)
+}
+
+/// Parse a plain-text map already held in memory (e.g. when there is no filesystem)
+pub fn parse_map_str(simulation: &mut Simulation, contents: &str) -> Result<(), ParseMapError> {
+    // First pass: validate syntax and assign ids
+    let mut scan = Scan::default();
+    for (line_idx, line) in contents.split('\n').enumerate() {
+        scan.line(line, line_idx + 1)?;
+    }
+    simulation.metadata = scan.metadata;
+    intern_colonies(simulation, scan.names)?;
     
-    // Create name-to-ID mapping with pre-allocated capacity
-    let mut name_to_id = HashMap::with_capacity(num_colonies);
-    for (i, (name, _)) in temp_colonies.iter().enumerate() {
-        if name_to_id.insert(name.clone(), i as ColonyId).is_some() {
-            return Err(format!("Duplicate colony: {name}"));
+    // Second pass: resolve neighbors by name
+    let mut colony_id: ColonyId = 0;
+    for (line_idx, line) in contents.split('\n').enumerate() {
+        if resolve_line(simulation, colony_id, line, line_idx + 1)? {
+            colony_id += 1;
         }
     }
     
-    // Second pass: build the graph structure
-    for (colony_id, (colony_name, connections)) in temp_colonies.iter().enumerate() {
-        simulation.set_colony_name(colony_id as ColonyId, colony_name.clone());
+    // Update valid direction bitmasks for all colonies
+    simulation.update_all_valid_directions();
+    
+    Ok(())
+}
+
+/// One-call constructors for the common case; parse into an existing `Simulation` (e.g. one
+/// reused across maps) with the free functions instead
+impl Simulation {
+    /// Build a simulation from a plain-text map held in memory, ready for `initialize_ants`
+    pub fn from_map_str(config: SimulationConfig, map: &str) -> Result<Self, ParseMapError> {
+        let mut simulation = Simulation::new(config);
+        parse_map_str(&mut simulation, map)?;
+        Ok(simulation)
+    }
+    
+    /// Build a simulation from a map file (see `parse_map_file`), ready for `initialize_ants`
+    pub fn from_map_file(config: SimulationConfig, path: &str) -> Result<Self, ParseMapError> {
+        let mut simulation = Simulation::new(config);
+        parse_map_file(&mut simulation, path)?;
+        Ok(simulation)
+    }
+}
+
+/// Parse a map by memory-mapping the file and walking borrowed line slices
+/// Avoids the per-line `String` allocations of `parse_map_file`: only the colony names are
+/// allocated. Produces the same `Simulation` as `parse_map_file` for plain-text maps.
+#[cfg(feature = "mmap")]
+pub fn parse_map_mmap(simulation: &mut Simulation, file_path: &str) -> Result<(), ParseMapError> {
+    let file = File::open(file_path)?;
+    // Safety: the mapping is read-only and only lives for this call; as with any mmap,
+    // the file must not be truncated by another process while it is being parsed
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    let contents = std::str::from_utf8(&mmap)
+        .map_err(|e| ParseMapError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+    
+    parse_map_str(simulation, contents)
+}
+
+/// Parse a JSON map: an array of objects with a `name` and optional direction fields
+/// e.g. `[{"name":"A","north":"B"},{"name":"B","south":"A"}]`; a `one_way` array of
+/// direction names marks those tunnels as deliberately one-way
+#[cfg(feature = "json")]
+pub fn parse_map_json(simulation: &mut Simulation, contents: &str) -> Result<(), ParseMapError> {
+    use serde_json::Value;
+    
+    let root: Value = serde_json::from_str(contents).map_err(|e| ParseMapError::InvalidJson(e.to_string()))?;
+    let entries = root.as_array()
+        .ok_or_else(|| ParseMapError::InvalidJson("map must be an array of colony objects".to_string()))?;
+    
+    // JSON maps have no metadata header, so drop any left by an earlier map
+    simulation.metadata = None;
+    let mut temp_colonies: Vec<RawColony> = Vec::with_capacity(entries.len());
+    let mut capacities = Vec::new();
+    for (entry_idx, entry) in entries.iter().enumerate() {
+        let entry_number = entry_idx + 1;
+        let object = entry.as_object()
+            .ok_or_else(|| ParseMapError::InvalidJson(format!("invalid colony entry: {entry}")))?;
+        let colony_name = object.get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ParseMapError::InvalidJson(format!("colony entry missing string \"name\": {entry}")))?;
+        
+        let mut connections = Vec::new();
+        for (key, value) in object {
+            if key == "name" || key == "one_way" {
+                continue;
+            }
+            if key == "capacity" {
+                let capacity = value.as_u64()
+                    .and_then(|capacity| u8::try_from(capacity).ok())
+                    .filter(|&capacity| capacity > 0)
+                    .ok_or_else(|| ParseMapError::InvalidCapacity { line: entry_number, token: value.to_string() })?;
+                capacities.push((entry_idx, capacity));
+                continue;
+            }
+            
+            let direction = key.parse::<Direction>()
+                .map_err(|_| ParseMapError::InvalidDirection { line: entry_number, token: key.clone() })?;
+            let neighbor_name = value.as_str()
+                .ok_or_else(|| ParseMapError::InvalidConnection { line: entry_number, token: format!("{key}={value}") })?;
+            
+            connections.push((direction, neighbor_name.to_string(), false));
+        }
         
-        for (direction, neighbor_name) in connections {
-            let neighbor_id = *name_to_id.get(neighbor_name)
-                .ok_or_else(|| format!("Unknown neighbor colony: {neighbor_name}"))?;
+        if let Some(value) = object.get("one_way") {
+            let directions = value.as_array()
+                .ok_or_else(|| ParseMapError::InvalidJson(format!("\"one_way\" must be an array of directions: {entry}")))?;
+            for direction in directions {
+                let name = direction.as_str().unwrap_or_default();
+                let direction = name.parse::<Direction>()
+                    .map_err(|_| ParseMapError::InvalidDirection { line: entry_number, token: direction.to_string() })?;
+                let connection = connections.iter_mut()
+                    .find(|(dir, _, _)| *dir == direction)
+                    .ok_or_else(|| ParseMapError::InvalidJson(format!("\"one_way\" names {name}, which has no tunnel: {entry}")))?;
+                connection.2 = true;
+            }
+        }
+        
+        temp_colonies.push((colony_name.to_string(), connections, entry_number));
+    }
+    
+    build_graph(simulation, temp_colonies)?;
+    for (colony_idx, capacity) in capacities {
+        simulation.colony_capacity[colony_idx] = capacity;
+    }
+    Ok(())
+}
+
+/// Assign colony ids and build the simulation graph from parsed colonies
+#[cfg(feature = "json")]
+fn build_graph(simulation: &mut Simulation, temp_colonies: Vec<RawColony>) -> Result<(), ParseMapError> {
+    let (names, connections): (Vec<_>, Vec<_>) = temp_colonies.into_iter()
+        .map(|(name, connections, line)| ((name, line), (connections, line)))
+        .unzip();
+    intern_colonies(simulation, names)?;
+    
+    for (colony_id, (connections, line)) in connections.into_iter().enumerate() {
+        for (direction, neighbor_name, one_way) in connections {
+            let neighbor_id = simulation.colony_names.id_of(&neighbor_name)
+                .ok_or(ParseMapError::UnknownNeighbor { line, name: neighbor_name })?;
             
-            simulation.set_neighbor(colony_id as ColonyId, *direction, neighbor_id);
+            simulation.set_neighbor(colony_id as ColonyId, direction, neighbor_id);
+            if one_way {
+                simulation.colony_one_way[colony_id] |= direction.as_bit_mask();
+            }
         }
     }
     
@@ -77,4 +496,20 @@ pub fn parse_map_file(simulation: &mut Simulation, file_path: &str) -> Result<()
     Ok(())
 }
 
-
+/// Open a map file for line-oriented reading, transparently decompressing `.gz` files
+fn open_map_reader(file_path: &str) -> Result<Box<dyn BufRead>, ParseMapError> {
+    let file = File::open(file_path)?;
+    
+    if file_path.ends_with(".gz") {
+        #[cfg(feature = "gzip")]
+        return Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(file))));
+        
+        #[cfg(not(feature = "gzip"))]
+        return Err(ParseMapError::Io(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("cannot read {file_path}: gzip support requires the `gzip` feature"),
+        )));
+    }
+    
+    Ok(Box::new(BufReader::new(file)))
+}
@@ -0,0 +1,4 @@
+//! The `std` prelude items the core needs, taken from `alloc` when building without `std`
+pub use alloc::string::{String, ToString};
+pub use alloc::vec::Vec;
+pub use alloc::{format, vec};
@@ -1,5 +1,6 @@
 /// Fast XorShift RNG for performance-critical simulation
 /// Much faster than standard cryptographic RNG
+#[derive(Debug, Clone)]
 pub struct FastRng {
     pub state: u64,
 }
@@ -23,6 +24,36 @@ impl FastRng {
         x as u32
     }
     
+    /// Fill a buffer with consecutive `next_u32` values in one tight loop
+    /// Produces exactly the values (and leaves the same state) as calling `next_u32` once per slot
+    #[inline]
+    pub fn fill_u32(&mut self, buf: &mut [u32]) {
+        let mut x = self.state;
+        for slot in buf.iter_mut() {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            *slot = x as u32;
+        }
+        self.state = x;
+    }
+    
+    /// Derive an independent generator, advancing this one by one step
+    /// The child is seeded with a SplitMix64 mix of the new state, so splitting a generator with a
+    /// given seed N times always yields the same N streams (e.g. one per worker, in worker order)
+    pub fn split(&mut self) -> FastRng {
+        self.next_u32();
+        FastRng::new(splitmix64(self.state))
+    }
+    
+    /// Shuffle `slice` in place (Fisher–Yates), drawing `len - 1` values with `next_range`
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.next_range(i as u32 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+    
     /// Generate random number in range [0, max) without modulo bias
     #[inline(always)]
     pub fn next_range(&mut self, max: u32) -> u32 {
@@ -42,4 +73,29 @@ impl FastRng {
             // Reject and try again - this happens rarely
         }
     }
-}
\ No newline at end of file
+    
+    /// Like `next_range`, for ranges wider than u32 (each draw takes two `next_u32` values)
+    #[inline]
+    pub fn next_range_u64(&mut self, max: u64) -> u64 {
+        if max == 0 {
+            return 0;
+        }
+        
+        let threshold = (u64::MAX / max) * max;
+        loop {
+            let value = (self.next_u32() as u64) << 32 | self.next_u32() as u64;
+            if value < threshold {
+                return value % max;
+            }
+        }
+    }
+}
+
+/// One SplitMix64 output for `state`: spreads nearby inputs (consecutive states, clock readings)
+/// over unrelated 64-bit values
+pub(crate) fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
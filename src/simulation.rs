@@ -1,62 +1,219 @@
-use std::collections::HashMap;
-use crate::types::{ColonyId, AntId, Direction, SimulationConfig};
+use crate::types::{ColonyId, AntId, Direction, SimulationConfig, SpawnMode};
 use crate::rng::FastRng;
+use crate::bitset::BitSet;
+use crate::interner::NameInterner;
+use crate::engine::{CaptureEvent, DeathCause, DestructionEvent, PhaseTimes, ReplayEvent};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 
 /// Core simulation structure using Struct-of-Arrays pattern for cache efficiency
+/// Cloning forks the full state (including the RNG), so both copies continue identically
+#[derive(Clone)]
 pub struct Simulation {
     // Colony data (SoA pattern - hot data accessed every iteration)
-    pub colony_valid: Vec<bool>,                    // Tombstoning - false means destroyed
+    pub colony_valid: BitSet,                       // Tombstoning - false means destroyed
     pub colony_north: Vec<Option<ColonyId>>,        // Neighbors in each direction
     pub colony_south: Vec<Option<ColonyId>>,
     pub colony_east: Vec<Option<ColonyId>>,
     pub colony_west: Vec<Option<ColonyId>>,
-    pub colony_valid_dirs: Vec<u8>,                 // Bitmask: bit 0=North, 1=South, 2=East, 3=West
+    pub colony_up: Vec<Option<ColonyId>>,           // Vertical links for multi-level maps
+    pub colony_down: Vec<Option<ColonyId>>,
+    pub colony_valid_dirs: Vec<u8>,                 // Bitmask: bit 0=North, 1=South, 2=East, 3=West, 4=Up, 5=Down
     
     // Ant tracking (Hybrid approach for O(1) collision detection)
     pub ant_colonies: Vec<ColonyId>,                // Current position of each ant
-    pub ant_alive: Vec<bool>,                       // Alive status for each ant
-    pub ant_moves: Vec<u16>,                        // Move counter for each ant
+    pub ant_alive: BitSet,                          // Alive status for each ant
+    pub ant_moves: Vec<u32>,                        // Move counter for each ant (never exceeds its move limit)
+    pub ant_max_moves: Vec<u32>,                    // Move limit of each ant (`max_moves`, or drawn from `max_moves_range`)
+    pub ant_prev_colony: Vec<Option<ColonyId>>,     // Colony each ant just left (for no-backtrack mode)
+    pub ant_death_cause: Vec<Option<DeathCause>>,   // Why each dead ant died (only with `death_causes`)
+    pub ant_oscillation_streak: Vec<u32>,           // Consecutive moves between the same two colonies (only with `oscillation_threshold`)
+    pub living_count: usize,                        // Incrementally maintained number of living ants
+    pub ants_at_max_moves: usize,                   // Living ants that have exhausted their move budget
     
     // Collision detection (Colony-centric for O(1) checks)
-    pub colony_ant_count: Vec<u8>,                  // Number of ants in each colony
+    pub colony_ant_count: Vec<AntId>,               // Number of ants in each colony
     pub colony_first_ant: Vec<Option<AntId>>,       // First ant in each colony (for collision messages)
+    pub colony_capacity: Vec<u8>,                   // Ants a colony holds before an arrival fights (`Name:K` in the map, default 1)
+    
+    // Opt-in statistics
+    pub colony_visits: Vec<u32>,                    // Times each colony was entered (only with `heatmap`)
+    pub colony_destroyed_at: Vec<Option<u32>>,      // Iteration each colony was destroyed in (0 = at spawn, None = standing)
     
     // Fast RNG
     pub rng: FastRng,
+    pub weighted_directions: bool,                  // False when all direction weights are equal (uniform fast path)
     
     // Cold data (rarely accessed during simulation)
-    pub colony_names: Vec<String>,                  // Original names for output
-    pub name_to_id: HashMap<String, ColonyId>,      // For parsing
+    pub colony_names: NameInterner,                 // Original names for output, and name → id lookup for parsing
+    pub metadata: Option<MapMetadata>,              // The map file's `%key: value` header, if it had one
+    pub colony_one_way: Vec<u8>,                    // Tunnels marked deliberately one-way (`east>=B`), same bits as `colony_valid_dirs`
     
     // Simulation state
     pub config: SimulationConfig,
     pub num_colonies: usize,
+    pub iteration: u32,                             // Iterations stepped so far (events are tagged 1-based)
+    pub total_collisions: u64,                      // Ants entering an occupied colony, destructive or not
+    pub captures: Vec<CaptureEvent>,                // Captures since the last `run_simulation` (non-destroying rules)
+    pub spawn_destructions: Vec<DestructionEvent>,  // Spawn-time fights (with `spawn_collisions`), moved into the next run's result
+    pub oscillations: Vec<(AntId, ColonyId, ColonyId)>, // Ants found oscillating, with the two colonies (lower id first)
+    pub phase_times: PhaseTimes,                    // Time spent in each step phase (only with `profile`)
+    pub ant_trace: Vec<String>,                     // What happened to `config.trace_ant`, one line per event
+    pub occupancy_snapshots: Vec<(u32, Vec<AntId>)>, // (iteration, `occupancy_snapshot()`) every `snapshot_every` iterations
+    pub ant_order: Vec<AntId>,                      // Scratch processing order for `shuffle_order`, refilled every step
+    pub recording: bool,                            // Collect `replay_events` (set by `run_simulation_recorded`)
+    pub replay_events: Vec<ReplayEvent>,            // Moves and deaths of the latest step (only while recording)
+}
+
+/// Packed valid-direction lists for every 6-bit mask, in bit order (N, S, E, W, Up, Down)
+static DIRECTION_LOOKUP: [[Option<Direction>; 6]; 64] = build_direction_lookup();
+
+const fn build_direction_lookup() -> [[Option<Direction>; 6]; 64] {
+    let mut table = [[None; 6]; 64];
+    let mut mask = 0;
+    while mask < 64 {
+        let mut count = 0;
+        let mut bit = 0;
+        while bit < 6 {
+            if mask & (1 << bit) != 0 {
+                table[mask][count] = Some(Direction::ALL[bit]);
+                count += 1;
+            }
+            bit += 1;
+        }
+        mask += 1;
+    }
+    table
+}
+
+/// Header of a map file: the `%key: value` lines before its first colony
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapMetadata {
+    pub name: Option<String>,                       // `%name:`
+    pub version: Option<String>,                    // `%version:`
+    pub fields: Vec<(String, String)>,              // Every other key, in file order
+}
+
+impl MapMetadata {
+    /// Record a header entry (a repeated key keeps its last value)
+    pub fn insert(&mut self, key: &str, value: &str) {
+        match key {
+            "name" => self.name = Some(value.to_string()),
+            "version" => self.version = Some(value.to_string()),
+            _ => match self.fields.iter_mut().find(|(existing, _)| existing == key) {
+                Some((_, existing)) => *existing = value.to_string(),
+                None => self.fields.push((key.to_string(), value.to_string())),
+            },
+        }
+    }
+    
+    /// Value of any header key, including `name` and `version`
+    pub fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "name" => self.name.as_deref(),
+            "version" => self.version.as_deref(),
+            _ => self.fields.iter().find(|(existing, _)| existing == key).map(|(_, value)| value.as_str()),
+        }
+    }
+}
+
+/// Errors from building a simulation by hand (the parser only passes ids it assigned itself)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimError {
+    ColonyOutOfRange { colony: ColonyId, num_colonies: usize },
+}
+
+impl core::fmt::Display for SimError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SimError::ColonyOutOfRange { colony, num_colonies } => write!(f, "colony id {} out of range (simulation has {} colonies)", colony, num_colonies),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SimError {}
+
+/// Seed used when none is configured, different on every call: SplitMix64 rounds over the
+/// wall-clock time in nanoseconds, the process id and a per-process call counter
+/// The derivation is not a stable interface; use `SimulationConfig::seed` to reproduce a run
+#[cfg(feature = "std")]
+pub fn default_seed() -> u64 {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use crate::rng::splitmix64;
+    
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    let calls = CALLS.fetch_add(1, Ordering::Relaxed);
+    splitmix64(splitmix64(splitmix64(nanos) ^ std::process::id() as u64) ^ calls)
+}
+
+/// Seed used when none is configured; without `std` there is no clock, so runs repeat unless
+/// `SimulationConfig::seed` is set
+#[cfg(not(feature = "std"))]
+pub fn default_seed() -> u64 {
+    1
+}
+
+/// Seed derived from the raw map bytes and ant count (64-bit FNV-1a, stable across platforms and builds)
+pub fn deterministic_seed(map_contents: &[u8], num_ants: AntId) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    
+    map_contents.iter()
+        .chain(&(num_ants as u64).to_le_bytes())
+        .fold(FNV_OFFSET, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
 }
 
 impl Simulation {
     pub fn new(config: SimulationConfig) -> Self {
-        let seed = config.seed.unwrap_or_else(|| {
-            use std::time::{SystemTime, UNIX_EPOCH};
-            SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
-        });
+        let seed = config.seed.unwrap_or_else(default_seed);
+        
+        let weights = config.direction_weights;
+        let weighted_directions = weights.iter().any(|&w| w != weights[0]);
         
         Self {
-            colony_valid: Vec::new(),
+            colony_valid: BitSet::new(),
             colony_north: Vec::new(),
             colony_south: Vec::new(),
             colony_east: Vec::new(),
             colony_west: Vec::new(),
+            colony_up: Vec::new(),
+            colony_down: Vec::new(),
             colony_valid_dirs: Vec::new(),
             ant_colonies: Vec::new(),
-            ant_alive: Vec::new(),
+            ant_alive: BitSet::new(),
             ant_moves: Vec::new(),
+            ant_max_moves: Vec::new(),
+            ant_prev_colony: Vec::new(),
+            ant_death_cause: Vec::new(),
+            ant_oscillation_streak: Vec::new(),
+            living_count: 0,
+            ants_at_max_moves: 0,
             colony_ant_count: Vec::new(),
             colony_first_ant: Vec::new(),
+            colony_capacity: Vec::new(),
+            colony_visits: Vec::new(),
+            colony_destroyed_at: Vec::new(),
             rng: FastRng::new(seed),
-            colony_names: Vec::new(),
-            name_to_id: HashMap::new(),
+            weighted_directions,
+            colony_names: NameInterner::new(),
+            metadata: None,
+            colony_one_way: Vec::new(),
             config,
             num_colonies: 0,
+            iteration: 0,
+            total_collisions: 0,
+            captures: Vec::new(),
+            spawn_destructions: Vec::new(),
+            oscillations: Vec::new(),
+            phase_times: PhaseTimes::default(),
+            ant_trace: Vec::new(),
+            occupancy_snapshots: Vec::new(),
+            ant_order: Vec::new(),
+            recording: false,
+            replay_events: Vec::new(),
         }
     }
     
@@ -68,12 +225,7 @@ impl Simulation {
             return None;
         }
         
-        match direction {
-            Direction::North => self.colony_north[idx],
-            Direction::South => self.colony_south[idx],
-            Direction::East => self.colony_east[idx],
-            Direction::West => self.colony_west[idx],
-        }
+        self.tunnel(idx, direction)
     }
     
     /// Efficient direction selection using lookup table
@@ -83,38 +235,46 @@ impl Simulation {
             return None;
         }
         
-        // Use a lookup table for all possible bit patterns (only 16 possibilities)
-        // Each entry contains the valid directions as a packed array
-        static DIRECTION_LOOKUP: [[Option<Direction>; 4]; 16] = [
-            [None, None, None, None],                                                                     // 0000
-            [Some(Direction::North), None, None, None],                                                   // 0001
-            [Some(Direction::South), None, None, None],                                                   // 0010
-            [Some(Direction::North), Some(Direction::South), None, None],                                 // 0011
-            [Some(Direction::East), None, None, None],                                                    // 0100
-            [Some(Direction::North), Some(Direction::East), None, None],                                  // 0101
-            [Some(Direction::South), Some(Direction::East), None, None],                                  // 0110
-            [Some(Direction::North), Some(Direction::South), Some(Direction::East), None],               // 0111
-            [Some(Direction::West), None, None, None],                                                    // 1000
-            [Some(Direction::North), Some(Direction::West), None, None],                                  // 1001
-            [Some(Direction::South), Some(Direction::West), None, None],                                  // 1010
-            [Some(Direction::North), Some(Direction::South), Some(Direction::West), None],               // 1011
-            [Some(Direction::East), Some(Direction::West), None, None],                                   // 1100
-            [Some(Direction::North), Some(Direction::East), Some(Direction::West), None],                // 1101
-            [Some(Direction::South), Some(Direction::East), Some(Direction::West), None],                // 1110
-            [Some(Direction::North), Some(Direction::South), Some(Direction::East), Some(Direction::West)], // 1111
-        ];
-        
+        // Use a lookup table for all possible bit patterns (64 possibilities for 6 directions)
+        // Each entry contains the valid directions as a packed array in bit order
         let directions = &DIRECTION_LOOKUP[valid_dirs_mask as usize];
-        let count = directions.iter().take_while(|d| d.is_some()).count();
+        let count = valid_dirs_mask.count_ones();
         
-        if count == 0 {
-            return None;
+        if self.weighted_directions {
+            if let Some(direction) = self.select_weighted_direction(&directions[..count as usize]) {
+                return Some(direction);
+            }
         }
         
-        let random_idx = self.rng.next_range(count as u32) as usize;
+        let random_idx = self.rng.next_range(count) as usize;
         directions[random_idx]
     }
     
+    /// Weighted sampling among the given valid directions using the configured weights
+    /// Returns None when every candidate has zero weight, so the caller can fall back to uniform
+    /// Weights are summed in u64, so any u32 weights work; totals that fit a u32 draw one value as before
+    fn select_weighted_direction(&mut self, candidates: &[Option<Direction>]) -> Option<Direction> {
+        let weights = &self.config.direction_weights;
+        let total: u64 = candidates.iter().flatten().map(|&d| weights[d as usize] as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        
+        let mut pick = match u32::try_from(total) {
+            Ok(total) => self.rng.next_range(total) as u64,
+            Err(_) => self.rng.next_range_u64(total),
+        };
+        for &direction in candidates.iter().flatten() {
+            let weight = weights[direction as usize] as u64;
+            if pick < weight {
+                return Some(direction);
+            }
+            pick -= weight;
+        }
+        
+        None
+    }
+    
     /// Update valid directions bitmask for a colony
     pub fn update_valid_directions(&mut self, colony_id: ColonyId) {
         let idx = colony_id as usize;
@@ -122,32 +282,52 @@ impl Simulation {
             return;
         }
         
-        let mut mask = 0u8;
-        
-        if let Some(neighbor_id) = self.colony_north[idx] {
-            if self.colony_valid[neighbor_id as usize] {
-                mask |= Direction::North.as_bit_mask();
-            }
-        }
-        if let Some(neighbor_id) = self.colony_south[idx] {
-            if self.colony_valid[neighbor_id as usize] {
-                mask |= Direction::South.as_bit_mask();
-            }
-        }
-        if let Some(neighbor_id) = self.colony_east[idx] {
-            if self.colony_valid[neighbor_id as usize] {
-                mask |= Direction::East.as_bit_mask();
-            }
-        }
-        if let Some(neighbor_id) = self.colony_west[idx] {
-            if self.colony_valid[neighbor_id as usize] {
-                mask |= Direction::West.as_bit_mask();
-            }
-        }
+        let mask = self.valid_neighbors(colony_id)
+            .fold(0u8, |mask, (direction, _)| mask | direction.as_bit_mask());
         
         self.colony_valid_dirs[idx] = mask;
     }
     
+    /// Raw tunnel lookup, ignoring whether either end has been destroyed
+    #[inline(always)]
+    pub(crate) fn tunnel(&self, idx: usize, direction: Direction) -> Option<ColonyId> {
+        match direction {
+            Direction::North => self.colony_north[idx],
+            Direction::South => self.colony_south[idx],
+            Direction::East => self.colony_east[idx],
+            Direction::West => self.colony_west[idx],
+            Direction::Up => self.colony_up[idx],
+            Direction::Down => self.colony_down[idx],
+        }
+    }
+    
+    /// Every tunnel out of a colony as (direction, neighbor), in north, south, east, west, up, down order
+    /// Includes tunnels to destroyed colonies; empty for out-of-range colonies
+    pub fn neighbors(&self, colony_id: ColonyId) -> impl Iterator<Item = (Direction, ColonyId)> + '_ {
+        let idx = colony_id as usize;
+        let directions: &[Direction] = if idx < self.num_colonies { &Direction::ALL } else { &[] };
+        directions.iter()
+            .filter_map(move |&direction| self.tunnel(idx, direction).map(|neighbor| (direction, neighbor)))
+    }
+    
+    /// Like `neighbors`, but only tunnels leading to colonies that have not been destroyed
+    pub fn valid_neighbors(&self, colony_id: ColonyId) -> impl Iterator<Item = (Direction, ColonyId)> + '_ {
+        self.neighbors(colony_id)
+            .filter(move |&(_, neighbor)| self.colony_valid.get(neighbor as usize))
+    }
+    
+    /// Bitmask of the directions from a colony whose tunnel leads to `target`
+    pub fn directions_towards(&self, colony_id: ColonyId, target: ColonyId) -> u8 {
+        self.neighbors(colony_id)
+            .filter(|&(_, neighbor)| neighbor == target)
+            .fold(0u8, |mask, (direction, _)| mask | direction.as_bit_mask())
+    }
+    
+    /// Whether the map marked the tunnel in `direction` from a colony as deliberately one-way
+    pub fn is_one_way(&self, colony_id: ColonyId, direction: Direction) -> bool {
+        self.colony_one_way.get(colony_id as usize).is_some_and(|&mask| mask & direction.as_bit_mask() != 0)
+    }
+    
     pub fn num_colonies(&self) -> usize {
         self.num_colonies
     }
@@ -156,6 +336,69 @@ impl Simulation {
         self.ant_colonies.len()
     }
     
+    /// Number of ants still alive (O(1) via the live counter)
+    pub fn num_living_ants(&self) -> usize {
+        self.living_count
+    }
+    
+    /// Number of colonies not yet destroyed (popcount over the validity bitset, 64 colonies per word)
+    pub fn num_valid_colonies(&self) -> usize {
+        self.colony_valid.count_ones()
+    }
+    
+    /// Iteration in which each colony was destroyed, indexed by colony id (`None` if still standing)
+    /// Counting entries at or below N gives how many colonies were gone after iteration N
+    pub fn destruction_times(&self) -> &[Option<u32>] {
+        &self.colony_destroyed_at
+    }
+    
+    /// Number of ants occupying each colony, indexed by colony id (a copy of the live counts)
+    pub fn occupancy_snapshot(&self) -> Vec<AntId> {
+        self.colony_ant_count.clone()
+    }
+    
+    /// Decode a colony's valid-direction bitmask into directions, in bit order
+    /// Empty for destroyed or out-of-range colonies
+    pub fn valid_directions(&self, colony_id: ColonyId) -> Vec<Direction> {
+        if !self.is_colony_valid(colony_id) {
+            return Vec::new();
+        }
+        
+        Direction::from_bit_mask(self.colony_valid_dirs[colony_id as usize]).collect()
+    }
+    
+    /// Check whether an ant is still alive
+    pub fn is_ant_alive(&self, ant_id: AntId) -> bool {
+        (ant_id as usize) < self.ant_alive.len() && self.ant_alive.get(ant_id as usize)
+    }
+    
+    /// Every living ant with the colony it is in, in id order
+    pub fn living_ants(&self) -> impl Iterator<Item = (AntId, ColonyId)> + '_ {
+        self.ant_alive.iter().enumerate()
+            .filter(|&(_, alive)| alive)
+            .map(|(ant_idx, _)| (ant_idx as AntId, self.ant_colonies[ant_idx]))
+    }
+    
+    /// Check whether a colony has not been destroyed
+    pub fn is_colony_valid(&self, colony_id: ColonyId) -> bool {
+        (colony_id as usize) < self.num_colonies && self.colony_valid.get(colony_id as usize)
+    }
+    
+    /// Id of the colony with this name (only meaningful once a map has been parsed)
+    pub fn colony_id(&self, name: &str) -> Option<ColonyId> {
+        self.colony_names.id_of(name)
+    }
+    
+    /// Header of the parsed map file (`%name: Hiveum`, `%version: 2`, ...), if it had one
+    pub fn metadata(&self) -> Option<&MapMetadata> {
+        self.metadata.as_ref()
+    }
+    
+    /// Name of a colony, or `None` for an out-of-range id (only meaningful once a map has been parsed)
+    pub fn colony_name(&self, colony_id: ColonyId) -> Option<&str> {
+        self.colony_names.get(colony_id)
+    }
+    
     /// Initialize simulation with given colony capacity
     pub fn initialize_with_capacity(&mut self, num_colonies: usize) {
         self.num_colonies = num_colonies;
@@ -166,40 +409,62 @@ impl Simulation {
         self.colony_south.resize(num_colonies, None);
         self.colony_east.resize(num_colonies, None);
         self.colony_west.resize(num_colonies, None);
+        self.colony_up.resize(num_colonies, None);
+        self.colony_down.resize(num_colonies, None);
         self.colony_valid_dirs.resize(num_colonies, 0);
         
         // Initialize collision detection structures
         self.colony_ant_count.resize(num_colonies, 0);
         self.colony_first_ant.resize(num_colonies, None);
+        self.colony_capacity.resize(num_colonies, 1);
+        self.colony_visits.resize(num_colonies, 0);
+        self.colony_destroyed_at.resize(num_colonies, None);
         
-        // Initialize cold data
-        self.colony_names.resize(num_colonies, String::new());
-        
-        // Reserve space for name mapping
-        self.name_to_id.reserve(num_colonies);
+        // Initialize cold data (names and their lookup index)
+        self.colony_names.resize(num_colonies);
+        self.colony_one_way.resize(num_colonies, 0);
     }
     
-    /// Set colony name (for output purposes)
-    pub fn set_colony_name(&mut self, colony_id: ColonyId, name: String) {
-        let idx = colony_id as usize;
-        if idx < self.num_colonies {
-            self.name_to_id.insert(name.clone(), colony_id);
-            self.colony_names[idx] = name;
-        }
+    /// Set colony name (for output purposes); an out-of-range id is ignored
+    pub fn set_colony_name(&mut self, colony_id: ColonyId, name: &str) {
+        let _ = self.try_set_colony_name(colony_id, name);
+    }
+    
+    /// Set colony name, failing if `colony_id` is not below `num_colonies`
+    pub fn try_set_colony_name(&mut self, colony_id: ColonyId, name: &str) -> Result<(), SimError> {
+        self.check_colony(colony_id)?;
+        self.colony_names.set(colony_id, name);
+        Ok(())
     }
     
-    /// Set neighbor colony in the given direction
+    /// Set neighbor colony in the given direction; out-of-range ids are ignored
     pub fn set_neighbor(&mut self, colony_id: ColonyId, direction: Direction, neighbor_id: ColonyId) {
-        let idx = colony_id as usize;
-        if idx >= self.num_colonies || neighbor_id as usize >= self.num_colonies {
-            return;
-        }
+        let _ = self.try_set_neighbor(colony_id, direction, neighbor_id);
+    }
+    
+    /// Set neighbor colony in the given direction, failing (and changing nothing) if either id
+    /// is not below `num_colonies`
+    pub fn try_set_neighbor(&mut self, colony_id: ColonyId, direction: Direction, neighbor_id: ColonyId) -> Result<(), SimError> {
+        self.check_colony(colony_id)?;
+        self.check_colony(neighbor_id)?;
         
+        let idx = colony_id as usize;
         match direction {
             Direction::North => self.colony_north[idx] = Some(neighbor_id),
             Direction::South => self.colony_south[idx] = Some(neighbor_id),
             Direction::East => self.colony_east[idx] = Some(neighbor_id),
             Direction::West => self.colony_west[idx] = Some(neighbor_id),
+            Direction::Up => self.colony_up[idx] = Some(neighbor_id),
+            Direction::Down => self.colony_down[idx] = Some(neighbor_id),
+        }
+        Ok(())
+    }
+    
+    fn check_colony(&self, colony_id: ColonyId) -> Result<(), SimError> {
+        if (colony_id as usize) < self.num_colonies {
+            Ok(())
+        } else {
+            Err(SimError::ColonyOutOfRange { colony: colony_id, num_colonies: self.num_colonies })
         }
     }
     
@@ -210,35 +475,206 @@ impl Simulation {
         }
     }
     
-    /// Initialize ants at random positions
-    pub fn initialize_ants(&mut self, num_ants: u16) {
+    /// Initialize ants, honoring `config.initial_placements` and placing the rest at random
+    /// in `config.spawn_colonies` (or anywhere on the map when that list is empty)
+    /// Fails if the map is empty, a placement or spawn colony names an unknown colony, or a placement uses an ant id outside `0..num_ants`
+    pub fn initialize_ants(&mut self, num_ants: AntId) -> Result<(), String> {
         if self.num_colonies == 0 {
-            return;
+            return Err("map file contains no colonies".to_string());
+        }
+        
+        let allowed: Vec<ColonyId> = if self.config.spawn_colonies.is_empty() {
+            (0..self.num_colonies).map(|idx| idx as ColonyId).collect()
+        } else {
+            self.config.spawn_colonies.iter()
+                .map(|name| self.colony_id(name)
+                    .ok_or_else(|| format!("Cannot spawn ants in unknown colony {name}")))
+                .collect::<Result<_, _>>()?
+        };
+        
+        self.initialize_ants_in(num_ants, &allowed)
+    }
+    
+    /// Initialize ants like `initialize_ants`, but place the randomly positioned ones only in `allowed` colonies
+    /// `num_ants` is an `AntId`, so every assigned ant id is guaranteed to fit without wrapping
+    /// Fails if none of the allowed colonies is valid
+    pub fn initialize_ants_in(&mut self, num_ants: AntId, allowed: &[ColonyId]) -> Result<(), String> {
+        // Draw straight from the valid candidates: no rejection loop, so sparse valid sets can't stall placement
+        let candidates: Vec<ColonyId> = allowed.iter()
+            .copied()
+            .filter(|&colony_id| self.is_colony_valid(colony_id))
+            .collect();
+        if candidates.is_empty() {
+            return Err("No valid colonies to spawn ants in".to_string());
+        }
+        
+        // Resolve placements up front so a bad name fails before any state changes
+        let mut placements: Vec<Option<ColonyId>> = vec![None; num_ants as usize];
+        for (ant_id, colony_name) in &self.config.initial_placements {
+            let colony_id = self.colony_id(colony_name)
+                .ok_or_else(|| format!("Cannot place ant {ant_id}: unknown colony {colony_name}"))?;
+            let slot = placements.get_mut(*ant_id as usize)
+                .ok_or_else(|| format!("Cannot place ant {ant_id}: only {num_ants} ants"))?;
+            *slot = Some(colony_id);
         }
         
         let num_ants = num_ants as usize;
         self.ant_colonies.resize(num_ants, 0);
         self.ant_alive.resize(num_ants, true);
         self.ant_moves.resize(num_ants, 0);
+        self.ant_prev_colony.resize(num_ants, None);
+        self.ant_death_cause.resize(num_ants, None);
+        self.ant_oscillation_streak.resize(num_ants, 0);
+        self.living_count = num_ants;
         
-        // Place ants randomly in valid colonies
-        for ant_id in 0..num_ants {
-            loop {
-                let colony_id = self.rng.next_range(self.num_colonies as u32) as ColonyId;
-                if self.colony_valid[colony_id as usize] {
-                    self.ant_colonies[ant_id] = colony_id;
-                    
-                    // Update colony occupancy
-                    let colony_idx = colony_id as usize;
-                    self.colony_ant_count[colony_idx] += 1;
-                    
-                    // Always update first_ant - overwrite with latest ant ID
-                    // This ensures we can always find at least one ant in the colony
-                    self.colony_first_ant[colony_idx] = Some(ant_id as AntId);
-                    
-                    break;
+        // Place ants in valid colonies (by `spawn_mode` unless explicitly placed)
+        for (ant_idx, placement) in placements.into_iter().enumerate() {
+            self.ant_colonies[ant_idx] = match (placement, self.config.spawn_mode) {
+                (Some(colony_id), _) => colony_id,
+                (None, SpawnMode::Random) => candidates[self.rng.next_range(candidates.len() as u32) as usize],
+                (None, SpawnMode::RoundRobin) => candidates[ant_idx % candidates.len()],
+            };
+        }
+        
+        // Move limits are drawn after placement, so a range doesn't change where ants start
+        self.ant_max_moves.clear();
+        match self.config.max_moves_range {
+            Some((min, max)) => {
+                for _ in 0..num_ants {
+                    let limit = match (max - min).checked_add(1) {
+                        Some(span) => min + self.rng.next_range(span),
+                        // `0..=u32::MAX` has a span of 2^32, so every u32 is a possible limit
+                        None => self.rng.next_u32(),
+                    };
+                    self.ant_max_moves.push(limit);
                 }
             }
+            None => self.ant_max_moves.resize(num_ants, self.config.max_moves),
+        }
+        self.ants_at_max_moves = self.ant_max_moves.iter().filter(|&&limit| limit == 0).count();
+        
+        // An ant with no moves at all starts settled and only occupies its colony if `settled_ants_block`
+        for ant_idx in 0..num_ants {
+            if self.ant_max_moves[ant_idx] > 0 || self.config.settled_ants_block {
+                self.occupy_colony(ant_idx as AntId, self.ant_colonies[ant_idx]);
+            }
+        }
+        
+        if self.config.spawn_collisions {
+            self.resolve_spawn_collisions();
+        }
+        
+        Ok(())
+    }
+    
+    /// Restore the parsed map to its undestroyed state and remove all ants, reseeding the RNG
+    /// Lets one parsed map be reused for many runs: call `initialize_ants` again afterwards
+    pub fn reset_ants(&mut self, seed: u64) {
+        self.rng = FastRng::new(seed);
+        self.config.seed = Some(seed);
+        
+        // Undo tombstoning and clear occupancy
+        self.colony_valid = BitSet::with_len(self.num_colonies, true);
+        self.colony_ant_count.fill(0);
+        self.colony_first_ant.fill(None);
+        self.colony_visits.fill(0);
+        self.colony_destroyed_at.fill(None);
+        self.update_all_valid_directions();
+        
+        // Drop all ants
+        self.ant_colonies.clear();
+        self.ant_alive = BitSet::new();
+        self.ant_moves.clear();
+        self.ant_max_moves.clear();
+        self.ant_prev_colony.clear();
+        self.ant_death_cause.clear();
+        self.ant_oscillation_streak.clear();
+        self.oscillations.clear();
+        self.living_count = 0;
+        self.ants_at_max_moves = 0;
+        self.iteration = 0;
+        self.total_collisions = 0;
+        self.captures.clear();
+        self.spawn_destructions.clear();
+        self.phase_times = PhaseTimes::default();
+        self.ant_trace.clear();
+        self.occupancy_snapshots.clear();
+        self.replay_events.clear();
+    }
+    
+    /// Remove the map and all ants, back to the empty state of `new` but keeping every allocation
+    /// for the next map (the different-map counterpart of `reset_ants`); the config and RNG are kept,
+    /// so reseed with `rng = FastRng::new(seed)` to repeat a fresh simulation's run exactly
+    pub fn clear(&mut self) {
+        self.num_colonies = 0;
+        self.colony_valid.resize(0, false);
+        for neighbors in [
+            &mut self.colony_north,
+            &mut self.colony_south,
+            &mut self.colony_east,
+            &mut self.colony_west,
+            &mut self.colony_up,
+            &mut self.colony_down,
+        ] {
+            neighbors.clear();
+        }
+        self.colony_valid_dirs.clear();
+        self.colony_ant_count.clear();
+        self.colony_first_ant.clear();
+        self.colony_capacity.clear();
+        self.colony_visits.clear();
+        self.colony_destroyed_at.clear();
+        self.colony_names.clear();
+        self.metadata = None;
+        self.colony_one_way.clear();
+        
+        self.ant_colonies.clear();
+        self.ant_alive.resize(0, false);
+        self.ant_moves.clear();
+        self.ant_max_moves.clear();
+        self.ant_prev_colony.clear();
+        self.ant_death_cause.clear();
+        self.ant_oscillation_streak.clear();
+        self.ant_order.clear();
+        self.oscillations.clear();
+        self.living_count = 0;
+        self.ants_at_max_moves = 0;
+        self.iteration = 0;
+        self.total_collisions = 0;
+        self.captures.clear();
+        self.spawn_destructions.clear();
+        self.phase_times = PhaseTimes::default();
+        self.ant_trace.clear();
+        self.occupancy_snapshots.clear();
+        self.replay_events.clear();
+    }
+    
+    /// Place an already-initialized living ant in a specific colony, moving it from its current one
+    pub fn place_ant(&mut self, ant_id: AntId, colony_id: ColonyId) -> Result<(), String> {
+        if !self.is_ant_alive(ant_id) {
+            return Err(format!("Cannot place ant {ant_id}: no such living ant"));
+        }
+        if !self.is_colony_valid(colony_id) {
+            return Err(format!("Cannot place ant {ant_id}: colony {colony_id} does not exist or is destroyed"));
+        }
+        
+        let current_colony = self.ant_colonies[ant_id as usize];
+        self.remove_ant_from_colony(ant_id, current_colony);
+        self.occupy_colony(ant_id, colony_id);
+        Ok(())
+    }
+    
+    /// Record an ant as standing in a colony and update occupancy
+    pub(crate) fn occupy_colony(&mut self, ant_id: AntId, colony_id: ColonyId) {
+        let colony_idx = colony_id as usize;
+        self.ant_colonies[ant_id as usize] = colony_id;
+        self.colony_ant_count[colony_idx] += 1;
+        
+        // Keep the lowest-id ant as the tracked occupant so collision
+        // messages don't depend on placement order
+        match self.colony_first_ant[colony_idx] {
+            Some(first) if first < ant_id => {}
+            _ => self.colony_first_ant[colony_idx] = Some(ant_id),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::engine::SimulationResult;
+use crate::simulation::Simulation;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
+/// Runs one parsed map once per seed, yielding each run's result
+/// e.g. `SeedSweep::new(sim, 1..=1000)?.map(|result| result.destructions.len())`
+/// Single-threaded and lazy: a seed is only drawn from `seeds` (and its run made) when `next`
+/// is called. Each run reuses the map's allocations via `reset_ants`, then places
+/// `config.num_ants` ants exactly as a fresh simulation with that seed would.
+pub struct SeedSweep<I> {
+    simulation: Simulation,
+    seeds: I,
+}
+
+impl<I: Iterator<Item = u64>> SeedSweep<I> {
+    /// Take ownership of a parsed simulation; fails if its ants can't be placed (e.g. an empty
+    /// map or a placement naming an unknown colony), which would fail the same way for every seed
+    pub fn new(mut simulation: Simulation, seeds: impl IntoIterator<IntoIter = I>) -> Result<Self, String> {
+        simulation.initialize_ants(simulation.config.num_ants)?;
+        Ok(SeedSweep { simulation, seeds: seeds.into_iter() })
+    }
+    
+    /// The simulation as the latest run left it
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+}
+
+impl<I: Iterator<Item = u64>> Iterator for SeedSweep<I> {
+    type Item = SimulationResult;
+    
+    fn next(&mut self) -> Option<SimulationResult> {
+        let seed = self.seeds.next()?;
+        self.simulation.reset_ants(seed);
+        self.simulation.initialize_ants(self.simulation.config.num_ants)
+            .expect("placement succeeded in SeedSweep::new and does not depend on the seed");
+        Some(self.simulation.run_simulation())
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.seeds.size_hint()
+    }
+}
@@ -1,22 +1,85 @@
+use core::time::Duration;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+
 /// Core types used throughout the simulation
+/// Id width defaults to u16; enable the `ids-u32` feature for maps or ant counts beyond 65,535
+#[cfg(not(feature = "ids-u32"))]
 pub type ColonyId = u16;
+#[cfg(not(feature = "ids-u32"))]
 pub type AntId = u16;
 
+#[cfg(feature = "ids-u32")]
+pub type ColonyId = u32;
+#[cfg(feature = "ids-u32")]
+pub type AntId = u32;
+
+/// `max_moves` for no per-ant move limit: an ant's move count can't reach it within `max_iterations`,
+/// so runs end when all ants are dead or one is left, or at the iteration cap
+pub const UNLIMITED_MOVES: u32 = u32::MAX;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Direction {
     North = 0,
     South = 1,
     East = 2,
     West = 3,
+    Up = 4,
+    Down = 5,
 }
 
 impl Direction {
+    /// Every direction in bit order (north, south, east, west, up, down)
+    pub const ALL: [Direction; 6] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+        Direction::Up,
+        Direction::Down,
+    ];
+    
     pub fn as_bit_mask(self) -> u8 {
         1 << (self as u8)
     }
+    
+    /// Every direction in bit order (same as `ALL`)
+    pub fn all() -> [Direction; 6] {
+        Self::ALL
+    }
+    
+    /// Decode a valid-direction bitmask into its directions, in bit order
+    /// For non-hot code; the movement hot path uses a precomputed lookup table instead
+    pub fn from_bit_mask(mask: u8) -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter().filter(move |direction| mask & direction.as_bit_mask() != 0)
+    }
+    
+    /// The direction leading back the way this one came (north/south, east/west, up/down)
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+    
+    /// Lowercase name as written in map files
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::North => "north",
+            Direction::South => "south",
+            Direction::East => "east",
+            Direction::West => "west",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        }
+    }
 }
 
-impl std::str::FromStr for Direction {
+impl core::str::FromStr for Direction {
     type Err = ();
     
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -25,6 +88,176 @@ impl std::str::FromStr for Direction {
             "south" => Ok(Direction::South),
             "east" => Ok(Direction::East),
             "west" => Ok(Direction::West),
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How results are written to stdout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Csv,
+    Counts,                                     // One tab-separated line: iterations, destructions, survivors
+}
+
+impl core::str::FromStr for OutputFormat {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            "counts" => Ok(OutputFormat::Counts),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Runtime targets used to judge a run in the performance summary (`--perf`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PerfThresholds {
+    pub small_map_colonies: usize,              // Maps with at most this many colonies count as small
+    pub small_map_target: Duration,
+    pub large_map_target: Duration,
+}
+
+impl Default for PerfThresholds {
+    fn default() -> Self {
+        Self {
+            small_map_colonies: 50,
+            small_map_target: Duration::from_millis(100),
+            large_map_target: Duration::from_secs(1),
+        }
+    }
+}
+
+impl PerfThresholds {
+    /// Same target for every map size
+    pub fn uniform(target: Duration) -> Self {
+        Self {
+            small_map_target: target,
+            large_map_target: target,
+            ..Self::default()
+        }
+    }
+    
+    /// The target for a map with `num_colonies` colonies
+    pub fn target_for(&self, num_colonies: usize) -> Duration {
+        if num_colonies <= self.small_map_colonies {
+            self.small_map_target
+        } else {
+            self.large_map_target
+        }
+    }
+}
+
+/// What happens when an ant enters an occupied colony
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CollisionRule {
+    #[default]
+    DestroyColony,                              // Both ants die and the colony is destroyed
+    LastWins,                                   // The occupant dies and the arriving ant captures the colony
+    FirstWins,                                  // The arriving ant dies and the occupant keeps the colony
+}
+
+impl core::str::FromStr for CollisionRule {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "destroy" => Ok(CollisionRule::DestroyColony),
+            "last-wins" => Ok(CollisionRule::LastWins),
+            "first-wins" => Ok(CollisionRule::FirstWins),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What happens to an ant whose target colony was destroyed earlier in the same iteration
+/// The puzzle only says ants "can no longer travel to" a destroyed colony, which reads closest to
+/// `StayPut`; `KillAnt` is the default so existing seeds reproduce their earlier results
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DestroyedTargetPolicy {
+    #[default]
+    KillAnt,                                    // The ant dies on its way into the rubble
+    StayPut,                                    // The ant stays where it is, but the attempt still uses a move
+}
+
+impl core::str::FromStr for DestroyedTargetPolicy {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kill" => Ok(DestroyedTargetPolicy::KillAnt),
+            "stay" => Ok(DestroyedTargetPolicy::StayPut),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How ants without an explicit placement are spread over the spawn colonies
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SpawnMode {
+    #[default]
+    Random,                                     // Each ant starts in a colony drawn from the RNG
+    RoundRobin,                                 // Ant i starts in the (i mod n)-th valid spawn colony, without the RNG
+}
+
+impl core::str::FromStr for SpawnMode {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(SpawnMode::Random),
+            "round-robin" => Ok(SpawnMode::RoundRobin),
+            _ => Err(()),
+        }
+    }
+}
+
+/// When text results use ANSI colors (destroyed colonies red, surviving colonies green)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    #[default]
+    Auto,                                       // Only when stdout is a terminal and `NO_COLOR` is unset
+    Always,
+    Never,
+}
+
+impl core::str::FromStr for ColorChoice {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Order of the surviving colony lines in the results
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColonySort {
+    #[default]
+    Line,                                       // Lexicographic by the full output line
+    Name,                                       // By colony name only
+    Degree,                                     // Most surviving connections first
+}
+
+impl core::str::FromStr for ColonySort {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "line" => Ok(ColonySort::Line),
+            "name" => Ok(ColonySort::Name),
+            "degree" => Ok(ColonySort::Degree),
             _ => Err(()),
         }
     }
@@ -33,19 +266,266 @@ impl std::str::FromStr for Direction {
 /// Simulation parameters
 #[derive(Debug, Clone)]
 pub struct SimulationConfig {
-    pub max_moves: u16,
-    pub num_ants: u16,
+    pub max_moves: u32,                         // Per-ant move limit (`UNLIMITED_MOVES` = none)
+    pub max_moves_range: Option<(u32, u32)>,    // Draw each ant's move limit from MIN..=MAX instead of using `max_moves`
+    pub max_iterations: u32,                    // Safety limit on simulation steps
+    #[cfg(feature = "std")]
+    pub time_limit: Option<Duration>,           // Wall-clock limit for `run_simulation` (needs a clock, so `std` only)
+    pub max_collisions: Option<u32>,            // Stop after the iteration in which this many colonies have been destroyed
+    pub max_total_moves: Option<u64>,           // Stop after the iteration in which the run's ant moves reach this total
+    pub warmup: u32,                            // Iterations run before the result starts recording (not checkpointed)
+    pub num_ants: AntId,
+    pub ant_density: Option<f64>,               // Ants per colony; replaces `num_ants` once the map is parsed
     pub map_file: String,
+    pub extra_map_files: Vec<String>,           // Further map files merged with `map_file` (cross-file tunnels allowed)
     pub seed: Option<u64>,
+    pub no_backtrack: bool,                     // Forbid returning to the previous colony unless it's the only move
+    pub direction_weights: [u32; 6],            // Relative preference per direction, indexed by `Direction as usize`
+    pub heatmap: bool,                          // Count how often each colony is entered
+    pub snapshot_every: Option<u32>,            // Copy every colony's ant count each N iterations
+    pub oscillation_threshold: Option<u32>,     // Report ants making this many consecutive moves between two colonies
+    pub death_causes: bool,                     // Record why each ant died
+    pub move_stats: bool,                       // Summarize how many moves each ant made
+    pub destroy_on_collision: bool,             // False: collisions are only counted, ants and colonies survive
+    pub sort_collision_ants: bool,              // Report the lower ant id first in destruction events (default: the mover first)
+    pub shuffle_order: bool,                    // Process ants in a fresh random order every step (default: ascending ids)
+    pub collision_rule: CollisionRule,          // Outcome of a fight (ignored when `destroy_on_collision` is false)
+    pub destroyed_target_policy: DestroyedTargetPolicy,
+    pub settled_ants_block: bool,               // Ants at the move limit still occupy their colony (false = others pass through)
+    pub initial_placements: Vec<(AntId, String)>, // Ants placed in named colonies instead of at random
+    pub spawn_colonies: Vec<String>,            // Restrict random placement to these colonies (empty = anywhere)
+    pub spawn_mode: SpawnMode,
+    pub spawn_collisions: bool,                 // Ants that start in the same colony fight before the first step
+    pub allow_oneway: bool,                     // One-way tunnels are intentional, so `validate` doesn't flag them
+    pub sort_colonies: ColonySort,
+    #[cfg(feature = "std")]
+    pub profile: bool,                          // Time parsing, placement and both step phases (not checkpointed, `std` only)
+    pub trace_ant: Option<AntId>,               // Describe every decision and fate of this ant
 }
 
 impl Default for SimulationConfig {
     fn default() -> Self {
         Self {
             max_moves: 10000,
+            max_moves_range: None,
+            max_iterations: 1_000_000,
+            #[cfg(feature = "std")]
+            time_limit: None,
+            max_collisions: None,
+            max_total_moves: None,
+            warmup: 0,
             num_ants: 0,
+            ant_density: None,
             map_file: String::new(),
+            extra_map_files: Vec::new(),
             seed: None,
+            no_backtrack: false,
+            direction_weights: [1; 6],
+            heatmap: false,
+            snapshot_every: None,
+            oscillation_threshold: None,
+            death_causes: false,
+            move_stats: false,
+            destroy_on_collision: true,
+            sort_collision_ants: false,
+            shuffle_order: false,
+            destroyed_target_policy: DestroyedTargetPolicy::KillAnt,
+            settled_ants_block: true,
+            collision_rule: CollisionRule::DestroyColony,
+            initial_placements: Vec::new(),
+            spawn_colonies: Vec::new(),
+            spawn_mode: SpawnMode::Random,
+            spawn_collisions: false,
+            allow_oneway: false,
+            sort_colonies: ColonySort::Line,
+            #[cfg(feature = "std")]
+            profile: false,
+            trace_ant: None,
         }
     }
+}
+
+impl SimulationConfig {
+    pub fn builder() -> SimulationConfigBuilder {
+        SimulationConfigBuilder::default()
+    }
+}
+
+/// Fluent builder for `SimulationConfig`; unset fields keep their `Default` values
+#[derive(Debug, Clone, Default)]
+pub struct SimulationConfigBuilder {
+    config: SimulationConfig,
+}
+
+impl SimulationConfigBuilder {
+    pub fn ants(mut self, num_ants: AntId) -> Self {
+        self.config.num_ants = num_ants;
+        self
+    }
+    
+    pub fn ant_density(mut self, density: Option<f64>) -> Self {
+        self.config.ant_density = density;
+        self
+    }
+    
+    pub fn map_file(mut self, map_file: impl Into<String>) -> Self {
+        self.config.map_file = map_file.into();
+        self
+    }
+    
+    pub fn extra_map_file(mut self, map_file: impl Into<String>) -> Self {
+        self.config.extra_map_files.push(map_file.into());
+        self
+    }
+    
+    pub fn max_moves(mut self, max_moves: u32) -> Self {
+        self.config.max_moves = max_moves;
+        self
+    }
+    
+    pub fn max_moves_range(mut self, range: Option<(u32, u32)>) -> Self {
+        self.config.max_moves_range = range;
+        self
+    }
+    
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.config.max_iterations = max_iterations;
+        self
+    }
+    
+    #[cfg(feature = "std")]
+    pub fn time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.config.time_limit = time_limit;
+        self
+    }
+    
+    pub fn max_collisions(mut self, max_collisions: Option<u32>) -> Self {
+        self.config.max_collisions = max_collisions;
+        self
+    }
+    
+    pub fn max_total_moves(mut self, max_total_moves: Option<u64>) -> Self {
+        self.config.max_total_moves = max_total_moves;
+        self
+    }
+    
+    pub fn warmup(mut self, warmup: u32) -> Self {
+        self.config.warmup = warmup;
+        self
+    }
+    
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.config.seed = seed;
+        self
+    }
+    
+    pub fn no_backtrack(mut self, no_backtrack: bool) -> Self {
+        self.config.no_backtrack = no_backtrack;
+        self
+    }
+    
+    pub fn direction_weights(mut self, weights: [u32; 6]) -> Self {
+        self.config.direction_weights = weights;
+        self
+    }
+    
+    pub fn heatmap(mut self, heatmap: bool) -> Self {
+        self.config.heatmap = heatmap;
+        self
+    }
+    
+    pub fn snapshot_every(mut self, every: Option<u32>) -> Self {
+        self.config.snapshot_every = every;
+        self
+    }
+    
+    pub fn oscillation_threshold(mut self, threshold: Option<u32>) -> Self {
+        self.config.oscillation_threshold = threshold;
+        self
+    }
+    
+    pub fn death_causes(mut self, death_causes: bool) -> Self {
+        self.config.death_causes = death_causes;
+        self
+    }
+    
+    pub fn move_stats(mut self, move_stats: bool) -> Self {
+        self.config.move_stats = move_stats;
+        self
+    }
+    
+    pub fn destroy_on_collision(mut self, destroy: bool) -> Self {
+        self.config.destroy_on_collision = destroy;
+        self
+    }
+    
+    pub fn sort_collision_ants(mut self, sort: bool) -> Self {
+        self.config.sort_collision_ants = sort;
+        self
+    }
+    
+    pub fn shuffle_order(mut self, shuffle: bool) -> Self {
+        self.config.shuffle_order = shuffle;
+        self
+    }
+    
+    pub fn destroyed_target_policy(mut self, policy: DestroyedTargetPolicy) -> Self {
+        self.config.destroyed_target_policy = policy;
+        self
+    }
+    
+    pub fn settled_ants_block(mut self, block: bool) -> Self {
+        self.config.settled_ants_block = block;
+        self
+    }
+    
+    pub fn collision_rule(mut self, rule: CollisionRule) -> Self {
+        self.config.collision_rule = rule;
+        self
+    }
+    
+    pub fn place(mut self, ant_id: AntId, colony_name: impl Into<String>) -> Self {
+        self.config.initial_placements.push((ant_id, colony_name.into()));
+        self
+    }
+    
+    pub fn spawn_in(mut self, colony_name: impl Into<String>) -> Self {
+        self.config.spawn_colonies.push(colony_name.into());
+        self
+    }
+    
+    pub fn spawn_mode(mut self, mode: SpawnMode) -> Self {
+        self.config.spawn_mode = mode;
+        self
+    }
+    
+    pub fn spawn_collisions(mut self, spawn_collisions: bool) -> Self {
+        self.config.spawn_collisions = spawn_collisions;
+        self
+    }
+    
+    pub fn allow_oneway(mut self, allow_oneway: bool) -> Self {
+        self.config.allow_oneway = allow_oneway;
+        self
+    }
+    
+    pub fn sort_colonies(mut self, sort: ColonySort) -> Self {
+        self.config.sort_colonies = sort;
+        self
+    }
+    
+    #[cfg(feature = "std")]
+    pub fn profile(mut self, profile: bool) -> Self {
+        self.config.profile = profile;
+        self
+    }
+    
+    pub fn trace_ant(mut self, ant_id: Option<AntId>) -> Self {
+        self.config.trace_ant = ant_id;
+        self
+    }
+    
+    pub fn build(self) -> SimulationConfig {
+        self.config
+    }
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+use wasm_bindgen::prelude::*;
+use serde_json::{json, Value};
+use crate::engine::SimulationResult;
+use crate::simulation::Simulation;
+use crate::types::{AntId, SimulationConfig};
+
+/// Parse `map` (plain-text format), run the simulation and return the result as JSON
+/// Errors are returned to JS as strings; nothing is printed and the process is never exited
+#[wasm_bindgen]
+pub fn run_simulation_json(map: &str, num_ants: u32, seed: u64, max_moves: u32) -> Result<String, JsValue> {
+    let num_ants = AntId::try_from(num_ants)
+        .map_err(|_| JsValue::from_str(&format!("{num_ants} ants exceeds maximum {}", AntId::MAX)))?;
+    
+    let config = SimulationConfig::builder()
+        .ants(num_ants)
+        .max_moves(max_moves)
+        .seed(Some(seed))
+        .build();
+    let mut sim = Simulation::from_map_str(config, map).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    sim.initialize_ants(num_ants).map_err(|e| JsValue::from_str(&e))?;
+    
+    let result = sim.run_simulation();
+    Ok(result_to_json(&result).to_string())
+}
+
+/// JSON shape returned to JS
+fn result_to_json(result: &SimulationResult) -> Value {
+    json!({
+        "iterations": result.iterations,
+        "termination": result.termination.to_string(),
+        "total_moves": result.total_moves,
+        "total_collisions": result.total_collisions,
+        "destructions": result.destructions.iter().map(|event| json!({
+            "iteration": event.iteration,
+            "colony": event.colony_name,
+            "ant_a": event.ant_a,
+            "ant_b": event.ant_b,
+            "message": event.to_string(),
+        })).collect::<Vec<_>>(),
+        "surviving_colonies": result.surviving_colonies,
+    })
+}
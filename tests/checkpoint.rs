@@ -0,0 +1,102 @@
+use ant_mania::simulation::Simulation;
+use ant_mania::{ColonySort, SimulationConfig};
+
+const MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/maps/hiveum_map_small.txt");
+
+/// A seeded simulation with every checkpointed statistic switched on, ants placed
+fn seeded_simulation() -> Simulation {
+    let config = SimulationConfig::builder()
+        .ants(40)
+        .seed(Some(7))
+        .heatmap(true)
+        .snapshot_every(Some(5))
+        .death_causes(true)
+        .move_stats(true)
+        .oscillation_threshold(Some(4))
+        .trace_ant(Some(3))
+        .build();
+    let mut simulation = Simulation::from_map_file(config, MAP).expect("map parses");
+    simulation.initialize_ants(40).expect("ants placed");
+    simulation
+}
+
+#[test]
+fn resumed_run_matches_uninterrupted_run() {
+    let mut uninterrupted = seeded_simulation();
+    let mut interrupted = seeded_simulation();
+    let mut destructions = Vec::new();
+    for _ in 0..10 {
+        uninterrupted.step(&mut destructions);
+        interrupted.step(&mut destructions);
+    }
+    
+    let mut resumed = Simulation::load_state(&interrupted.save_state()).expect("checkpoint loads");
+    assert_eq!(resumed.save_state(), interrupted.save_state());
+    
+    let expected = uninterrupted.run_simulation();
+    let actual = resumed.run_simulation();
+    assert!(expected.iterations > 0);
+    assert_eq!(actual, expected);
+    assert_eq!(resumed.save_state(), uninterrupted.save_state());
+    assert_eq!(resumed.ant_trace, uninterrupted.ant_trace);
+    assert_eq!(resumed.occupancy_snapshots, uninterrupted.occupancy_snapshots);
+}
+
+#[test]
+fn resumed_run_keeps_the_colony_sort() {
+    let config = SimulationConfig::builder()
+        .ants(20)
+        .seed(Some(11))
+        .sort_colonies(ColonySort::Degree)
+        .build();
+    let mut uninterrupted = Simulation::from_map_file(config, MAP).expect("map parses");
+    uninterrupted.initialize_ants(20).expect("ants placed");
+    let mut interrupted = uninterrupted.clone();
+    let mut destructions = Vec::new();
+    for _ in 0..3 {
+        uninterrupted.step(&mut destructions);
+        interrupted.step(&mut destructions);
+    }
+    
+    let mut resumed = Simulation::load_state(&interrupted.save_state()).expect("checkpoint loads");
+    assert_eq!(resumed.config.sort_colonies, ColonySort::Degree);
+    let expected = uninterrupted.run_simulation();
+    // Only meaningful if the degree order differs from the default line order
+    let mut by_line = expected.surviving_colonies.clone();
+    by_line.sort();
+    assert_ne!(expected.surviving_colonies, by_line);
+    assert_eq!(resumed.run_simulation(), expected);
+}
+
+#[test]
+fn checkpoint_keeps_statistics_config() {
+    let simulation = seeded_simulation();
+    let restored = Simulation::load_state(&simulation.save_state()).expect("checkpoint loads");
+    assert_eq!(restored.config.snapshot_every, Some(5));
+    assert!(restored.config.move_stats);
+    assert_eq!(restored.config.trace_ant, Some(3));
+}
+
+#[test]
+fn load_state_rejects_truncated_checkpoint() {
+    let bytes = seeded_simulation().save_state();
+    assert!(Simulation::load_state(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Simulation::load_state(b"not a checkpoint").is_err());
+}
+
+#[test]
+fn checkpoint_keeps_a_total_move_limit_past_u32() {
+    let limit = u32::MAX as u64 + 1;
+    let simulation = Simulation::new(SimulationConfig::builder().max_total_moves(Some(limit)).build());
+    let restored = Simulation::load_state(&simulation.save_state()).expect("checkpoint loads");
+    assert_eq!(restored.config.max_total_moves, Some(limit));
+}
+
+#[test]
+fn checkpoint_keeps_one_way_tunnels() {
+    let config = SimulationConfig::builder().allow_oneway(true).build();
+    let simulation = Simulation::from_map_str(config, "A east>=B\nB\n").expect("map parses");
+    let restored = Simulation::load_state(&simulation.save_state()).expect("checkpoint loads");
+    assert!(restored.config.allow_oneway);
+    assert_eq!(restored.map_lines(ColonySort::Line), simulation.map_lines(ColonySort::Line));
+}
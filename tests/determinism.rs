@@ -0,0 +1,104 @@
+use ant_mania::parser::parse_map_file;
+use ant_mania::rng::FastRng;
+use ant_mania::simulation::Simulation;
+use ant_mania::{AntId, SimulationConfig};
+
+const MEDIUM_MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/maps/hiveum_map_medium.txt");
+const SMALL_MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/maps/hiveum_map_small.txt");
+
+fn placed_simulation(config: SimulationConfig, num_ants: AntId) -> Simulation {
+    let mut simulation = Simulation::new(config);
+    parse_map_file(&mut simulation, MEDIUM_MAP).expect("map parses");
+    simulation.initialize_ants(num_ants).expect("ants placed");
+    simulation
+}
+
+#[test]
+fn same_seed_gives_identical_results() {
+    let config = SimulationConfig::builder().seed(Some(1)).max_moves(2000).build();
+    let first = placed_simulation(config.clone(), 3000).run_simulation();
+    let second = placed_simulation(config, 3000).run_simulation();
+    
+    assert!(!first.destructions.is_empty());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn colony_counts_match_occupants_every_step() {
+    // Enough ants that many spawn together, exercising removal from shared colonies
+    let config = SimulationConfig::builder().seed(Some(5)).max_moves(300).build();
+    let mut simulation = placed_simulation(config, 8000);
+    let mut destructions = Vec::new();
+    while !simulation.is_finished() {
+        simulation.step(&mut destructions);
+        
+        let mut occupants = vec![0usize; simulation.num_colonies()];
+        for ant in 0..simulation.num_ants() {
+            if simulation.is_ant_alive(ant as AntId) {
+                occupants[simulation.ant_colonies[ant] as usize] += 1;
+            }
+        }
+        for (colony, &expected) in occupants.iter().enumerate() {
+            assert_eq!(simulation.colony_ant_count[colony] as usize, expected, "colony {colony}");
+        }
+    }
+}
+
+#[test]
+fn cloned_simulation_continues_identically_until_reseeded() {
+    let config = SimulationConfig::builder().seed(Some(9)).max_moves(2000).build();
+    let mut original = placed_simulation(config, 2000);
+    let mut destructions = Vec::new();
+    for _ in 0..5 {
+        original.step(&mut destructions);
+    }
+    
+    let mut fork = original.clone();
+    let mut reseeded = original.clone();
+    reseeded.rng = FastRng::new(10);
+    
+    let expected = original.run_simulation();
+    assert_eq!(fork.run_simulation(), expected);
+    assert_ne!(reseeded.run_simulation(), expected);
+}
+
+#[test]
+fn rng_splits_are_reproducible_and_distinct() {
+    let sub_seeds = |seed: u64| -> Vec<u64> {
+        let mut rng = FastRng::new(seed);
+        (0..8).map(|_| rng.split().state).collect()
+    };
+    
+    let streams = sub_seeds(42);
+    assert_eq!(streams, sub_seeds(42));
+    assert_ne!(streams, sub_seeds(43));
+    let mut distinct = streams.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), streams.len());
+}
+
+#[test]
+fn cleared_simulation_reruns_like_a_fresh_one() {
+    let config = SimulationConfig::builder().seed(Some(11)).max_moves(2000).build();
+    let mut reused = placed_simulation(config.clone(), 4000);
+    let _ = reused.run_simulation();
+    
+    reused.clear();
+    reused.rng = FastRng::new(11);
+    parse_map_file(&mut reused, SMALL_MAP).expect("map parses");
+    let mut fresh = Simulation::from_map_file(config, SMALL_MAP).expect("map parses");
+    assert!(reused.save_state() == fresh.save_state());
+    
+    reused.initialize_ants(30).expect("ants placed");
+    fresh.initialize_ants(30).expect("ants placed");
+    assert_eq!(reused.run_simulation(), fresh.run_simulation());
+}
+
+#[test]
+fn unseeded_simulations_get_different_rng_states() {
+    let states: Vec<u64> = (0..4).map(|_| Simulation::new(SimulationConfig::default()).rng.state).collect();
+    for (i, state) in states.iter().enumerate() {
+        assert!(!states[i + 1..].contains(state), "back-to-back unseeded simulations shared RNG state {state}");
+    }
+}
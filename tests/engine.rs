@@ -0,0 +1,202 @@
+use ant_mania::engine::{DeathCause, TerminationReason};
+use ant_mania::simulation::Simulation;
+use ant_mania::{AntId, CollisionRule, DestroyedTargetPolicy, Direction, SimulationConfig, UNLIMITED_MOVES};
+
+/// A hub `X` with three dead-end spokes; every ant on a spoke can only move into the hub
+const HUB_MAP: &str = "X west=P east=Q north=R\nP east=X\nQ west=X\nR south=X\n";
+
+/// Ants 0, 1 and 2 on spokes P, Q and R: in the first step ants 0 and 1 destroy the hub,
+/// after ant 2 has already chosen to move there
+fn hub_simulation(policy: DestroyedTargetPolicy) -> Simulation {
+    let config = SimulationConfig::builder()
+        .destroyed_target_policy(policy)
+        .death_causes(true)
+        .place(0, "P")
+        .place(1, "Q")
+        .place(2, "R")
+        .build();
+    let mut simulation = Simulation::from_map_str(config, HUB_MAP).expect("map parses");
+    simulation.initialize_ants(3).expect("ants placed");
+    simulation
+}
+
+#[test]
+fn trapped_ants_stop_at_a_move_limit_beyond_u16() {
+    // Two isolated colonies, so neither ant can ever move or meet the other
+    let max_moves = u16::MAX as u32 + 10;
+    let config = SimulationConfig::builder()
+        .max_moves(max_moves)
+        .place(0, "A")
+        .place(1, "B")
+        .build();
+    let mut simulation = Simulation::from_map_str(config, "A\nB\n").expect("map parses");
+    simulation.initialize_ants(2).expect("ants placed");
+    
+    let result = simulation.run_simulation();
+    assert_eq!(result.termination, TerminationReason::AllAntsReachedMaxMoves);
+    assert_eq!(result.iterations, max_moves);
+    assert_eq!(simulation.ant_moves, vec![max_moves; 2]);
+}
+
+#[test]
+#[ignore = "steps 4.3 billion trapped-ant moves; run with `cargo test --release -- --ignored`"]
+fn total_moves_count_past_u32_max() {
+    // Every ant shares one isolated colony, so each step is one trapped move per ant
+    let num_ants = u16::MAX as AntId;
+    let max_moves = u16::MAX as u32 + 5;
+    let config = SimulationConfig::builder().max_moves(max_moves).seed(Some(1)).build();
+    let mut simulation = Simulation::from_map_str(config, "A\n").expect("map parses");
+    simulation.initialize_ants(num_ants).expect("ants placed");
+    
+    let result = simulation.run_simulation();
+    assert_eq!(result.termination, TerminationReason::AllAntsReachedMaxMoves);
+    assert_eq!(result.total_moves, num_ants as u64 * max_moves as u64);
+    assert!(result.total_moves > u32::MAX as u64);
+}
+
+#[test]
+#[ignore = "steps 4.3 billion trapped-ant moves; run with `cargo test --release -- --ignored`"]
+fn unlimited_moves_count_past_u32_max() {
+    // Without a move limit only `max_iterations` ends the run
+    let num_ants = u16::MAX as AntId;
+    let max_iterations = u16::MAX as u32 + 5;
+    let config = SimulationConfig::builder()
+        .max_moves(UNLIMITED_MOVES)
+        .max_iterations(max_iterations)
+        .seed(Some(1))
+        .build();
+    let mut simulation = Simulation::from_map_str(config, "A\n").expect("map parses");
+    simulation.initialize_ants(num_ants).expect("ants placed");
+    
+    let result = simulation.run_simulation();
+    assert_eq!(result.termination, TerminationReason::IterationLimitExceeded);
+    assert_eq!(result.total_moves, num_ants as u64 * max_iterations as u64);
+    assert!(result.total_moves > u32::MAX as u64);
+}
+
+#[test]
+fn placement_finds_the_few_valid_colonies_quickly() {
+    // 60k isolated colonies, all but the last destroyed: a rejection loop would need ~60k draws per ant
+    let map: String = (0..60_000).map(|i| format!("C{i}\n")).collect();
+    let mut simulation = Simulation::from_map_str(SimulationConfig::builder().seed(Some(3)).build(), &map)
+        .expect("map parses");
+    let last = simulation.num_colonies() - 1;
+    for colony_idx in 0..last {
+        simulation.colony_valid.set(colony_idx, false);
+    }
+    
+    let start = std::time::Instant::now();
+    simulation.initialize_ants(1000).expect("ants placed");
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    assert!(simulation.ant_colonies.iter().all(|&colony| colony as usize == last));
+}
+
+#[test]
+fn kill_ant_policy_kills_an_ant_heading_into_a_destroyed_colony() {
+    let mut simulation = hub_simulation(DestroyedTargetPolicy::KillAnt);
+    let hub = simulation.colony_id("X").unwrap();
+    let mut destructions = Vec::new();
+    simulation.step(&mut destructions);
+    
+    assert_eq!(destructions.len(), 1);
+    assert_eq!(simulation.num_living_ants(), 0);
+    assert_eq!(simulation.ant_death_cause[2], Some(DeathCause::EnteredDestroyedColony(hub)));
+}
+
+#[test]
+fn stay_put_policy_keeps_an_ant_heading_into_a_destroyed_colony() {
+    let mut simulation = hub_simulation(DestroyedTargetPolicy::StayPut);
+    let mut destructions = Vec::new();
+    simulation.step(&mut destructions);
+    
+    assert_eq!(destructions.len(), 1);
+    assert_eq!(simulation.living_ants().collect::<Vec<_>>(), vec![(2, simulation.colony_id("R").unwrap())]);
+    assert_eq!(simulation.ant_moves[2], 1);
+    assert_eq!(simulation.ant_death_cause[2], None);
+}
+
+/// Ants 0 and 1 on either side of colony X, both forced to move into it in the first step
+fn head_on_destruction(config: SimulationConfig) -> String {
+    let config = SimulationConfig { initial_placements: vec![(0, "P".to_string()), (1, "Q".to_string())], ..config };
+    let mut simulation = Simulation::from_map_str(config, "X west=P east=Q\nP east=X\nQ west=X\n").expect("map parses");
+    simulation.initialize_ants(2).expect("ants placed");
+    let mut destructions = Vec::new();
+    simulation.step(&mut destructions);
+    assert_eq!(destructions.len(), 1);
+    destructions[0].to_string()
+}
+
+#[test]
+fn collision_message_lists_mover_first_by_default() {
+    // Ant 0 moves in first, so ant 1 is the one that arrives to fight
+    assert_eq!(head_on_destruction(SimulationConfig::default()), "X has been destroyed by ant 1 and ant 0!");
+}
+
+#[test]
+fn sorted_collision_message_lists_lower_id_first() {
+    // Shuffled processing lets either ant arrive first, depending on the seed
+    for seed in 0..16 {
+        let config = SimulationConfig::builder()
+            .seed(Some(seed))
+            .shuffle_order(true)
+            .sort_collision_ants(true)
+            .build();
+        assert_eq!(head_on_destruction(config), "X has been destroyed by ant 0 and ant 1!");
+    }
+}
+
+#[test]
+fn direction_weights_summing_past_u32_max_do_not_overflow() {
+    let config = SimulationConfig::builder()
+        .seed(Some(4))
+        .direction_weights([u32::MAX, 1, u32::MAX, u32::MAX, 0, 0])
+        .build();
+    let mut simulation = Simulation::new(config);
+    
+    // North, south, east and west are valid; south's share is about one in 13 billion
+    let picks: Vec<_> = (0..1000).map(|_| simulation.select_random_direction(0b1111)).collect();
+    assert!(picks.iter().all(|pick| matches!(pick, Some(Direction::North | Direction::East | Direction::West))));
+    for direction in [Direction::North, Direction::East, Direction::West] {
+        assert!(picks.contains(&Some(direction)));
+    }
+}
+
+#[test]
+fn full_move_limit_range_can_draw_u32_max() {
+    let config = SimulationConfig::builder()
+        .max_moves_range(Some((0, u32::MAX)))
+        .place(0, "A")
+        .build();
+    let mut simulation = Simulation::from_map_str(config, "A\n").expect("map parses");
+    // The explicit placement takes no randomness, and from this state `next_u32` returns u32::MAX
+    simulation.rng.state = 0xeb86_d055_5c5d_0210;
+    simulation.initialize_ants(1).expect("ants placed");
+    assert_eq!(simulation.ant_max_moves, vec![u32::MAX]);
+}
+
+#[test]
+fn settled_ant_is_never_picked_as_the_occupant() {
+    // Ants 0, 1 and 2 start in the dead end X; ants 3 and 4 can only move into it
+    let config = SimulationConfig::builder()
+        .seed(Some(25))
+        .max_moves_range(Some((0, 3)))
+        .settled_ants_block(false)
+        .collision_rule(CollisionRule::LastWins)
+        .place(0, "X")
+        .place(1, "X")
+        .place(2, "X")
+        .place(3, "A")
+        .place(4, "B")
+        .build();
+    let mut simulation = Simulation::from_map_str(config, "X\nA east=X\nB west=X\n").expect("map parses");
+    simulation.initialize_ants(5).expect("ants placed");
+    // Ant 0 starts settled, so it sits in X without occupying it
+    assert_eq!(simulation.ant_max_moves, vec![0, 2, 3, 3, 1]);
+    
+    // Ant 3 captures X from ant 1, then ant 4 from ant 2; ant 0 takes no part
+    simulation.step(&mut Vec::new());
+    simulation.check_invariants();
+    let fights: Vec<_> = simulation.captures.iter().map(|capture| (capture.winner, capture.loser)).collect();
+    assert_eq!(fights, vec![(3, 1), (4, 2)]);
+    assert!(simulation.is_ant_alive(0));
+}
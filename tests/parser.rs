@@ -0,0 +1,96 @@
+use ant_mania::analysis::ValidationWarning;
+use ant_mania::parser::parse_map_file;
+use ant_mania::simulation::Simulation;
+use ant_mania::{ColonySort, Direction, SimulationConfig};
+
+const SMALL_MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/maps/hiveum_map_small.txt");
+const CRLF_SMALL_MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/hiveum_map_small_crlf.txt");
+const GZIPPED_SMALL_MAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/hiveum_map_small.txt.gz");
+
+/// Parse with a fixed seed, so two parses of the same map checkpoint to the same bytes
+fn parse(path: &str) -> Result<Simulation, String> {
+    let mut simulation = Simulation::new(SimulationConfig::builder().seed(Some(1)).build());
+    parse_map_file(&mut simulation, path).map_err(|e| e.to_string())?;
+    Ok(simulation)
+}
+
+#[test]
+fn crlf_map_parses_like_lf_map() {
+    let crlf = parse(CRLF_SMALL_MAP).expect("CRLF map parses");
+    let lf = parse(SMALL_MAP).expect("LF map parses");
+    assert_eq!(crlf.num_colonies(), 28);
+    assert!(crlf.colony_names.iter().all(|name| !name.contains('\r')));
+    assert!(crlf.save_state() == lf.save_state());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzipped_map_parses_like_plain_map() {
+    let gzipped = parse(GZIPPED_SMALL_MAP).expect("gzipped map parses");
+    let plain = parse(SMALL_MAP).expect("plain map parses");
+    assert_eq!(gzipped.num_colonies(), 28);
+    assert!(gzipped.save_state() == plain.save_state());
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzipped_map_needs_gzip_feature() {
+    assert!(parse(SMALL_MAP).is_ok());
+    let error = parse(GZIPPED_SMALL_MAP).err().expect("gzip is unsupported");
+    assert!(error.contains("gzip"), "{error}");
+}
+
+#[test]
+fn byte_order_mark_and_zero_width_spaces_separate_tokens() {
+    let config = SimulationConfig::builder().seed(Some(1)).build();
+    let plain = Simulation::from_map_str(config.clone(), "A north=B\nB south=A\n").expect("map parses");
+    let invisible = Simulation::from_map_str(config, "\u{feff}A\u{200b}north=B\u{200b}\nB south=A\n").expect("map parses");
+    assert_eq!(invisible.colony_id("A"), Some(0));
+    assert!(invisible.save_state() == plain.save_state());
+}
+
+/// The tunnel-back warnings `validate` reports, as (from, to) pairs
+fn asymmetric_edges(simulation: &Simulation) -> Vec<(String, String)> {
+    simulation.validate().into_iter()
+        .filter_map(|warning| match warning {
+            ValidationWarning::AsymmetricEdge { from, to, .. } => Some((from, to)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn one_way_marker_excuses_only_its_own_tunnel() {
+    let simulation = Simulation::from_map_str(SimulationConfig::default(), "A east>=B north=C\nB\nC\n").expect("map parses");
+    assert!(simulation.is_one_way(0, Direction::East));
+    assert!(!simulation.is_one_way(0, Direction::North));
+    assert_eq!(asymmetric_edges(&simulation), vec![("A".to_string(), "C".to_string())]);
+    assert_eq!(simulation.map_lines(ColonySort::Line)[0], "A north=C east>=B");
+    
+    let allowed = Simulation::from_map_str(SimulationConfig::builder().allow_oneway(true).build(), "A east>=B north=C\nB\nC\n")
+        .expect("map parses");
+    assert!(asymmetric_edges(&allowed).is_empty());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_one_way_array_marks_tunnels() {
+    let map = r#"[{"name": "A", "east": "B", "north": "C", "one_way": ["east"]}, {"name": "B"}, {"name": "C"}]"#;
+    let mut simulation = Simulation::new(SimulationConfig::default());
+    ant_mania::parser::parse_map_json(&mut simulation, map).expect("map parses");
+    assert!(simulation.is_one_way(0, Direction::East));
+    assert!(!simulation.is_one_way(0, Direction::North));
+    assert_eq!(asymmetric_edges(&simulation), vec![("A".to_string(), "C".to_string())]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_map_drops_metadata_from_an_earlier_text_map() {
+    let mut simulation = Simulation::new(SimulationConfig::default());
+    ant_mania::parser::parse_map_str(&mut simulation, "%name: Hiveum\nX north=Y\nY south=X\n").expect("map parses");
+    assert!(simulation.metadata.is_some());
+    
+    ant_mania::parser::parse_map_json(&mut simulation, r#"[{"name": "A", "north": "B"}, {"name": "B", "south": "A"}]"#)
+        .expect("map parses");
+    assert!(simulation.metadata.is_none());
+}
@@ -0,0 +1,18 @@
+use ant_mania::Direction;
+
+#[test]
+fn opposite_directions_pair_up() {
+    let pairs = [
+        (Direction::North, Direction::South),
+        (Direction::East, Direction::West),
+        (Direction::Up, Direction::Down),
+    ];
+    for (direction, opposite) in pairs {
+        assert_eq!(direction.opposite(), opposite);
+        assert_eq!(opposite.opposite(), direction);
+    }
+    for direction in Direction::ALL {
+        assert_ne!(direction.opposite(), direction);
+        assert_eq!(direction.opposite().opposite(), direction);
+    }
+}